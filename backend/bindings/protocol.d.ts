@@ -0,0 +1,67 @@
+// Generated by `gen-types`. Do not edit by hand.
+
+export type GamePhase = "lobby" | "submissions" | "in_progress" | "finished";
+
+export type GiftState = "unopened" | "opened";
+
+export type GiftRecord = { id: string, submitted_by: string, product_url: string, hint: string, image_url: string | null, title: string | null, opened_by: string | null, held_by: string | null, stolen_count: number, state: GiftState, };
+
+export type PlayerView = { id: string, name: string, joined_at: number, 
+/**
+ * Whether the player has at least one live socket.
+ */
+online: boolean, };
+
+export type Rules = { 
+/**
+ * A gift is frozen with its holder once it has been stolen this many times.
+ */
+max_steals_per_gift: number, };
+
+export type GameView = { id: string, phase: GamePhase, players: Array<PlayerView>, gifts: Array<GiftRecord>, turn_order: Array<string>, 
+/**
+ * Together with `history` and `rules`, lets a client feed this view
+ * straight into the game-wasm rules to preview moves.
+ */
+current_turn: number, active_player: string | null, 
+/**
+ * Grows with the game, but is bounded: one open and at most
+ * `max_steals_per_gift` steals per gift, each with its turn change, so
+ * about 250 events at the default 30 players. Delta connections are sent
+ * only each move's new events.
+ */
+history: Array<GameEvent>, rules: Rules, spectators: number, };
+
+export type PlayerAction = { "choose_gift": { player_id: string, gift_id: string, } } | { "steal_gift": { player_id: string, gift_id: string, } };
+
+export type GameEvent = { "type": "gift_opened", player_id: string, gift_id: string, } | { "type": "gift_stolen", from: string, to: string, gift_id: string, } | { "type": "turn_changed", player_id: string, } | { "type": "game_finished" };
+
+export type GiftTally = { gift_id: string, count: number, };
+
+export type PlayerTally = { player_id: string, count: number, };
+
+export type GameStats = { total_steals: number, 
+/**
+ * Gift stolen the most times; ties go to the gift that got there first.
+ */
+most_stolen_gift: GiftTally | null, 
+/**
+ * Most steals in a row before someone opened a fresh gift.
+ */
+longest_steal_chain: number, 
+/**
+ * Player who had gifts taken from them the most; ties go the same way.
+ */
+most_robbed_player: PlayerTally | null, 
+/**
+ * Gifts still held by whoever opened them.
+ */
+kept_by_opener: Array<string>, };
+
+export type JsonValue = number | string | boolean | Array<JsonValue> | { [key in string]?: JsonValue } | null;
+
+export type PatchOp = { "op": "add", path: string, value: JsonValue, } | { "op": "remove", path: string, } | { "op": "replace", path: string, value: JsonValue, };
+
+export type ServerMessage = { "type": "state" } & GameView | { "type": "event", event: GameEvent, } | { "type": "stats" } & GameStats | { "type": "spectators", count: number, } | { "type": "presence_changed", player_id: string, online: boolean, } | { "type": "welcome", protocol_version: number, features: Array<string>, } | { "type": "snapshot", seq: number, state: GameView, } | { "type": "patch", base: number, seq: number, ops: Array<PatchOp>, };
+
+export type ClientMessage = { "type": "hello", protocol_version: number, features?: Array<string>, } | { "type": "action" } & PlayerAction | { "type": "resync" };
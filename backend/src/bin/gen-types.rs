@@ -0,0 +1,5 @@
+//! Print TypeScript declarations for the wire types to stdout.
+
+fn main() {
+    print!("{}", backend::typescript::definitions());
+}
@@ -0,0 +1,163 @@
+//! Wire encodings a socket can negotiate, either with `?encoding=` or the
+//! `Sec-WebSocket-Protocol` header. JSON text frames stay the default; the
+//! binary encodings use binary frames. Plain-text `error:` replies are sent
+//! as text frames whatever the encoding.
+
+use axum::extract::ws::Message;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Json,
+    Msgpack,
+    Cbor,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Msgpack(#[from] rmp_serde::decode::Error),
+    #[error(transparent)]
+    Cbor(#[from] ciborium::de::Error<std::io::Error>),
+}
+
+impl Encoding {
+    /// Offered subprotocols, most compact first.
+    pub const SUBPROTOCOLS: [&'static str; 3] = ["elephant.msgpack", "elephant.cbor", "elephant.json"];
+
+    pub fn from_subprotocol(name: &str) -> Option<Self> {
+        match name {
+            "elephant.json" => Some(Encoding::Json),
+            "elephant.msgpack" => Some(Encoding::Msgpack),
+            "elephant.cbor" => Some(Encoding::Cbor),
+            _ => None,
+        }
+    }
+
+    pub fn is_binary(self) -> bool {
+        self != Encoding::Json
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Message {
+        match self {
+            Encoding::Json => Message::Text(serde_json::to_string(value).expect("message serializes")),
+            // Named fields: internally tagged enums need maps to find their tag.
+            Encoding::Msgpack => {
+                Message::Binary(rmp_serde::to_vec_named(value).expect("message serializes"))
+            }
+            Encoding::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf).expect("message serializes");
+                Message::Binary(buf)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, DecodeError> {
+        Ok(match self {
+            Encoding::Json => serde_json::from_slice(bytes)?,
+            Encoding::Msgpack => rmp_serde::from_slice(bytes)?,
+            Encoding::Cbor => ciborium::from_reader(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientMessage, ServerMessage};
+    use game_core::{GameEvent, PlayerAction};
+
+    fn all_events() -> Vec<GameEvent> {
+        let events = vec![
+            GameEvent::GiftOpened {
+                player_id: "p1".into(),
+                gift_id: "g1".into(),
+            },
+            GameEvent::GiftStolen {
+                from: "p1".into(),
+                to: "p2".into(),
+                gift_id: "g1".into(),
+            },
+            GameEvent::TurnChanged {
+                player_id: "p1".into(),
+            },
+            GameEvent::GameFinished,
+        ];
+        // Fails to compile when a variant is added without a case above.
+        for event in &events {
+            match event {
+                GameEvent::GiftOpened { .. }
+                | GameEvent::GiftStolen { .. }
+                | GameEvent::TurnChanged { .. }
+                | GameEvent::GameFinished => {}
+            }
+        }
+        events
+    }
+
+    fn all_actions() -> Vec<PlayerAction> {
+        let actions = vec![
+            PlayerAction::ChooseGift {
+                player_id: "p1".into(),
+                gift_id: "g1".into(),
+            },
+            PlayerAction::StealGift {
+                player_id: "p2".into(),
+                gift_id: "g1".into(),
+            },
+        ];
+        for action in &actions {
+            match action {
+                PlayerAction::ChooseGift { .. } | PlayerAction::StealGift { .. } => {}
+            }
+        }
+        actions
+    }
+
+    fn bytes(msg: Message) -> Vec<u8> {
+        match msg {
+            Message::Text(text) => text.into_bytes(),
+            Message::Binary(bytes) => bytes,
+            other => panic!("unexpected frame {other:?}"),
+        }
+    }
+
+    #[test]
+    fn every_event_and_action_round_trips() {
+        for encoding in [Encoding::Json, Encoding::Msgpack, Encoding::Cbor] {
+            for event in all_events() {
+                let frame = encoding.encode(&ServerMessage::Event {
+                    event: event.clone(),
+                });
+                assert_eq!(matches!(frame, Message::Binary(_)), encoding.is_binary());
+                let decoded: ServerMessage = encoding.decode(&bytes(frame)).unwrap();
+                assert!(
+                    matches!(&decoded, ServerMessage::Event { event: e } if *e == event),
+                    "{encoding:?}: {decoded:?}"
+                );
+            }
+            for action in all_actions() {
+                let frame = encoding.encode(&ClientMessage::Action(action.clone()));
+                let decoded: ClientMessage = encoding.decode(&bytes(frame)).unwrap();
+                assert!(
+                    matches!(&decoded, ClientMessage::Action(a) if *a == action),
+                    "{encoding:?}: {decoded:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn subprotocol_names_map_to_encodings() {
+        for name in Encoding::SUBPROTOCOLS {
+            assert!(Encoding::from_subprotocol(name).is_some());
+        }
+        assert_eq!(Encoding::from_subprotocol("graphql-ws"), None);
+    }
+}
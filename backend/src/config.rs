@@ -0,0 +1,347 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use game_core::Rules;
+use serde::Deserialize;
+
+pub const DEFAULT_ADMIN_PASSWORD: &str = "changeme";
+
+/// Server settings, resolved from defaults, an optional TOML file, env vars
+/// and CLI flags (later sources win).
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+    pub persist_path: Option<PathBuf>,
+    pub broadcast_capacity: usize,
+    /// How often sockets are pinged.
+    pub ws_ping_interval_ms: u64,
+    /// Pings that may go unanswered before a socket is dropped.
+    pub ws_max_missed_pongs: u32,
+    /// Pause before each bot move, so a table can follow them one at a time.
+    pub bot_move_delay_ms: u64,
+    pub static_dir: Option<PathBuf>,
+    pub admin_password: String,
+    pub insecure_dev: bool,
+    pub log_format: LogFormat,
+    pub rules: Rules,
+    pub limits: Limits,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human-readable lines for local runs.
+    #[default]
+    Text,
+    /// One JSON object per line for log shippers.
+    Json,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Limits {
+    pub max_players: usize,
+    pub max_games: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 3000,
+            persist_path: None,
+            broadcast_capacity: 32,
+            ws_ping_interval_ms: 15_000,
+            ws_max_missed_pongs: 2,
+            bot_move_delay_ms: 800,
+            static_dir: None,
+            admin_password: DEFAULT_ADMIN_PASSWORD.to_string(),
+            insecure_dev: false,
+            log_format: LogFormat::default(),
+            rules: Rules::default(),
+            limits: Limits::default(),
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_players: 30,
+            max_games: 100,
+        }
+    }
+}
+
+#[derive(Debug, Default, Parser)]
+#[command(name = "backend", about = "White elephant game server")]
+pub struct Cli {
+    /// TOML config file; flags and env vars override its values.
+    #[arg(long, env = "CONFIG_PATH")]
+    pub config: Option<PathBuf>,
+    #[arg(long, env = "HOST")]
+    pub host: Option<String>,
+    #[arg(long, env = "PORT")]
+    pub port: Option<u16>,
+    /// JSON file used to save and restore games across restarts.
+    #[arg(long, env = "PERSIST_PATH")]
+    pub persist_path: Option<PathBuf>,
+    /// Buffered messages per game before slow sockets start lagging.
+    #[arg(long, env = "BROADCAST_CAPACITY")]
+    pub broadcast_capacity: Option<usize>,
+    #[arg(long, env = "WS_PING_INTERVAL_MS")]
+    pub ws_ping_interval_ms: Option<u64>,
+    #[arg(long, env = "WS_MAX_MISSED_PONGS")]
+    pub ws_max_missed_pongs: Option<u32>,
+    #[arg(long, env = "BOT_MOVE_DELAY_MS")]
+    pub bot_move_delay_ms: Option<u64>,
+    /// Frontend build to serve; takes precedence over embedded assets.
+    #[arg(long, env = "STATIC_DIR")]
+    pub static_dir: Option<PathBuf>,
+    #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+    pub admin_password: Option<String>,
+    /// Allow the default admin password. Local development only.
+    #[arg(long, env = "INSECURE_DEV", value_parser = clap::builder::BoolishValueParser::new())]
+    pub insecure_dev: bool,
+    /// Log output format; verbosity is controlled with RUST_LOG.
+    #[arg(long, env = "LOG_FORMAT", value_enum)]
+    pub log_format: Option<LogFormat>,
+    #[arg(long, env = "MAX_STEALS")]
+    pub max_steals: Option<u8>,
+    #[arg(long, env = "MAX_PLAYERS")]
+    pub max_players: Option<usize>,
+    #[arg(long, env = "MAX_GAMES")]
+    pub max_games: Option<usize>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("refusing to start with the default admin password; set ADMIN_PASSWORD or pass --insecure-dev")]
+    DefaultAdminPassword,
+    #[error("{0} must be greater than zero")]
+    MustBePositive(&'static str),
+}
+
+impl Config {
+    /// Resolve config from the process args and environment.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::from_cli(Cli::parse())
+    }
+
+    pub fn from_cli(cli: Cli) -> Result<Self, ConfigError> {
+        let mut config = match &cli.config {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        if let Some(host) = cli.host {
+            config.host = host;
+        }
+        if let Some(port) = cli.port {
+            config.port = port;
+        }
+        if let Some(path) = cli.persist_path {
+            config.persist_path = Some(path);
+        }
+        if let Some(capacity) = cli.broadcast_capacity {
+            config.broadcast_capacity = capacity;
+        }
+        if let Some(interval) = cli.ws_ping_interval_ms {
+            config.ws_ping_interval_ms = interval;
+        }
+        if let Some(missed) = cli.ws_max_missed_pongs {
+            config.ws_max_missed_pongs = missed;
+        }
+        if let Some(delay) = cli.bot_move_delay_ms {
+            config.bot_move_delay_ms = delay;
+        }
+        if let Some(dir) = cli.static_dir {
+            config.static_dir = Some(dir);
+        }
+        if let Some(password) = cli.admin_password {
+            config.admin_password = password;
+        }
+        if cli.insecure_dev {
+            config.insecure_dev = true;
+        }
+        if let Some(format) = cli.log_format {
+            config.log_format = format;
+        }
+        if let Some(max_steals) = cli.max_steals {
+            config.rules.max_steals_per_gift = max_steals;
+        }
+        if let Some(max_players) = cli.max_players {
+            config.limits.max_players = max_players;
+        }
+        if let Some(max_games) = cli.max_games {
+            config.limits.max_games = max_games;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&text).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.uses_default_password() && !self.insecure_dev {
+            return Err(ConfigError::DefaultAdminPassword);
+        }
+        if self.broadcast_capacity == 0 {
+            return Err(ConfigError::MustBePositive("broadcast_capacity"));
+        }
+        if self.ws_ping_interval_ms == 0 {
+            return Err(ConfigError::MustBePositive("ws_ping_interval_ms"));
+        }
+        if self.limits.max_players == 0 {
+            return Err(ConfigError::MustBePositive("max_players"));
+        }
+        if self.limits.max_games == 0 {
+            return Err(ConfigError::MustBePositive("max_games"));
+        }
+        Ok(())
+    }
+
+    pub fn uses_default_password(&self) -> bool {
+        self.admin_password.is_empty() || self.admin_password == DEFAULT_ADMIN_PASSWORD
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn write_config(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("ce_config_{}.toml", Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn refuses_default_password_without_insecure_dev() {
+        let err = Config::from_cli(Cli::default()).unwrap_err();
+        assert!(matches!(err, ConfigError::DefaultAdminPassword));
+
+        let config = Config::from_cli(Cli {
+            insecure_dev: true,
+            ..Cli::default()
+        })
+        .unwrap();
+        assert!(config.uses_default_password());
+    }
+
+    #[test]
+    fn file_values_are_overridden_by_flags() {
+        let path = write_config(
+            r#"
+            port = 8080
+            admin_password = "from-file"
+            broadcast_capacity = 64
+            log_format = "json"
+
+            [rules]
+            max_steals_per_gift = 2
+
+            [limits]
+            max_players = 12
+            "#,
+        );
+
+        let config = Config::from_cli(Cli {
+            config: Some(path.clone()),
+            port: Some(9000),
+            max_steals: Some(1),
+            ..Cli::default()
+        })
+        .unwrap();
+
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.admin_password, "from-file");
+        assert_eq!(config.broadcast_capacity, 64);
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.rules.max_steals_per_gift, 1);
+        assert_eq!(config.limits.max_players, 12);
+        assert_eq!(config.limits.max_games, Limits::default().max_games);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_zero_capacity() {
+        let path = write_config("prot = 8080\n");
+        let err = Config::from_cli(Cli {
+            config: Some(path.clone()),
+            ..Cli::default()
+        })
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::Parse { .. }));
+        let _ = std::fs::remove_file(path);
+
+        let err = Config::from_cli(Cli {
+            admin_password: Some("secret".into()),
+            broadcast_capacity: Some(0),
+            ..Cli::default()
+        })
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::MustBePositive("broadcast_capacity")
+        ));
+
+        for (cli, field) in [
+            (
+                Cli {
+                    max_players: Some(0),
+                    ..Cli::default()
+                },
+                "max_players",
+            ),
+            (
+                Cli {
+                    max_games: Some(0),
+                    ..Cli::default()
+                },
+                "max_games",
+            ),
+        ] {
+            let err = Config::from_cli(Cli {
+                admin_password: Some("secret".into()),
+                ..cli
+            })
+            .unwrap_err();
+            assert!(matches!(err, ConfigError::MustBePositive(f) if f == field));
+        }
+    }
+
+    #[test]
+    fn insecure_dev_env_accepts_boolish_values() {
+        // Nothing else in the test binary reads INSECURE_DEV.
+        for (value, expected) in [("1", true), ("yes", true), ("true", true), ("0", false)] {
+            std::env::set_var("INSECURE_DEV", value);
+            let cli = Cli::try_parse_from(["backend"]).unwrap();
+            assert_eq!(cli.insecure_dev, expected, "INSECURE_DEV={value}");
+        }
+        std::env::remove_var("INSECURE_DEV");
+    }
+}
@@ -0,0 +1,224 @@
+//! Delta mode: instead of a full `GameView` after every action, a connection
+//! gets JSON-patch style operations against the last state it was sent.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::{GameView, ServerMessage};
+
+/// Subset of RFC 6902 operations; paths are JSON pointers.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, TS)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// Per-connection sequencing of views. Sequence numbers start at 1 with the
+/// first snapshot and go up by one for every state sent after it.
+#[derive(Debug, Default)]
+pub struct DeltaEncoder {
+    seq: u64,
+    last: Option<Value>,
+}
+
+impl DeltaEncoder {
+    pub fn encode(&mut self, view: GameView) -> ServerMessage {
+        let value = serde_json::to_value(&view).expect("game view serializes");
+        self.seq += 1;
+        let msg = match self.last.take() {
+            Some(prev) => ServerMessage::Patch {
+                base: self.seq - 1,
+                seq: self.seq,
+                ops: diff(&prev, &value),
+            },
+            None => ServerMessage::Snapshot {
+                seq: self.seq,
+                state: view,
+            },
+        };
+        self.last = Some(value);
+        msg
+    }
+
+    /// Make the next state go out as a full snapshot.
+    pub fn resync(&mut self) {
+        self.last = None;
+    }
+}
+
+pub fn diff(old: &Value, new: &Value) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    diff_at(&mut String::new(), old, new, &mut ops);
+    ops
+}
+
+fn diff_at(path: &mut String, old: &Value, new: &Value, ops: &mut Vec<PatchOp>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let len = push_segment(path, key);
+                match new.get(key) {
+                    Some(new_value) => diff_at(path, old_value, new_value, ops),
+                    None => ops.push(PatchOp::Remove { path: path.clone() }),
+                }
+                path.truncate(len);
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    let len = push_segment(path, key);
+                    ops.push(PatchOp::Add {
+                        path: path.clone(),
+                        value: new_value.clone(),
+                    });
+                    path.truncate(len);
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for (index, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                let len = push_segment(path, &index.to_string());
+                diff_at(path, old_value, new_value, ops);
+                path.truncate(len);
+            }
+            for (index, value) in new.iter().enumerate().skip(old.len()) {
+                ops.push(PatchOp::Add {
+                    path: format!("{path}/{index}"),
+                    value: value.clone(),
+                });
+            }
+            // Remove from the end so earlier indices stay valid.
+            for index in (new.len()..old.len()).rev() {
+                ops.push(PatchOp::Remove {
+                    path: format!("{path}/{index}"),
+                });
+            }
+        }
+        (old, new) if old != new => ops.push(PatchOp::Replace {
+            path: path.clone(),
+            value: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Appends an escaped pointer segment and returns the length to truncate
+/// back to.
+fn push_segment(path: &mut String, segment: &str) -> usize {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    len
+}
+
+/// Reference implementation of what clients do with a patch.
+#[cfg(test)]
+pub fn apply(doc: &mut Value, ops: &[PatchOp]) {
+    for op in ops {
+        let (path, value) = match op {
+            PatchOp::Add { path, value } | PatchOp::Replace { path, value } => {
+                (path, Some(value.clone()))
+            }
+            PatchOp::Remove { path } => (path, None),
+        };
+        if path.is_empty() {
+            *doc = value.expect("cannot remove the root");
+            continue;
+        }
+        let (parent, last) = path.rsplit_once('/').unwrap();
+        let key = last.replace("~1", "/").replace("~0", "~");
+        match doc.pointer_mut(parent).expect("patch parent exists") {
+            Value::Object(map) => match value {
+                Some(value) => {
+                    map.insert(key, value);
+                }
+                None => {
+                    map.remove(&key);
+                }
+            },
+            Value::Array(items) => {
+                let index: usize = key.parse().unwrap();
+                match (op, value) {
+                    (PatchOp::Add { .. }, Some(value)) => items.insert(index, value),
+                    (_, Some(value)) => items[index] = value,
+                    (_, None) => {
+                        items.remove(index);
+                    }
+                }
+            }
+            other => panic!("cannot patch into {other}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_applies_back_to_the_new_value() {
+        let old = json!({
+            "phase": "in_progress",
+            "active_player": "p1",
+            "gone": true,
+            "a/b~c": 1,
+            "gifts": [
+                { "id": "g1", "held_by": null },
+                { "id": "g2", "held_by": null },
+                { "id": "g3", "held_by": null }
+            ],
+            "players": [{ "id": "p1" }]
+        });
+        let new = json!({
+            "phase": "in_progress",
+            "active_player": "p2",
+            "a/b~c": 2,
+            "gifts": [
+                { "id": "g1", "held_by": "p1", "stolen_count": 0 },
+                { "id": "g2", "held_by": null }
+            ],
+            "players": [{ "id": "p1" }, { "id": "p2" }, { "id": "p3" }]
+        });
+
+        let ops = diff(&old, &new);
+        assert!(ops.contains(&PatchOp::Replace {
+            path: "/active_player".into(),
+            value: json!("p2"),
+        }));
+        assert!(ops.contains(&PatchOp::Replace {
+            path: "/a~1b~0c".into(),
+            value: json!(2),
+        }));
+        assert!(ops.contains(&PatchOp::Remove {
+            path: "/gifts/2".into()
+        }));
+
+        let mut patched = old.clone();
+        apply(&mut patched, &ops);
+        assert_eq!(patched, new);
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn a_longer_history_patches_as_appends() {
+        let event = |n: usize| json!({ "type": "turn_changed", "player_id": format!("p{n}") });
+        let old = json!({ "history": (0..200).map(event).collect::<Vec<_>>() });
+        let new = json!({ "history": (0..202).map(event).collect::<Vec<_>>() });
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                PatchOp::Add {
+                    path: "/history/200".into(),
+                    value: event(200),
+                },
+                PatchOp::Add {
+                    path: "/history/201".into(),
+                    value: event(201),
+                },
+            ]
+        );
+    }
+}
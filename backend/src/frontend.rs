@@ -0,0 +1,183 @@
+//! Serves the SPA build alongside the API, either from a directory on disk or
+//! from assets baked in with the `embed-frontend` feature.
+
+use std::path::{Path, PathBuf};
+
+use axum::extract::{Request, State};
+use axum::handler::Handler;
+use axum::http::{header, HeaderValue, StatusCode, Uri};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use tower_http::compression::CompressionLayer;
+use tower_http::services::ServeDir;
+
+const NO_CACHE: &str = "no-cache";
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const SHORT_CACHE: &str = "public, max-age=3600";
+
+/// Router used as the app fallback, or `None` when there is nothing to serve.
+pub fn router(static_dir: Option<&Path>) -> Option<Router> {
+    let router = match static_dir {
+        Some(dir) => serve_dir(dir),
+        None => embedded_router()?,
+    };
+    Some(
+        router
+            .layer(middleware::from_fn(cache_control))
+            .layer(CompressionLayer::new()),
+    )
+}
+
+fn serve_dir(dir: &Path) -> Router {
+    let index = dir.join("index.html");
+    Router::new().fallback_service(
+        ServeDir::new(dir)
+            .precompressed_br()
+            .precompressed_gzip()
+            .fallback(spa_fallback.with_state(index)),
+    )
+}
+
+/// Client-side routes get `index.html`; missing files (anything with an
+/// extension) stay 404 so broken asset links are not masked.
+async fn spa_fallback(State(index): State<PathBuf>, uri: Uri) -> Response {
+    if looks_like_file(uri.path()) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match tokio::fs::read(&index).await {
+        Ok(bytes) => (
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn looks_like_file(path: &str) -> bool {
+    path.rsplit('/').next().is_some_and(|name| name.contains('.'))
+}
+
+/// HTML must be revalidated so new deploys are picked up; hashed bundles under
+/// `/assets/` never change and can be cached forever. The policy comes from the
+/// request path rather than the response, because a 304 carries no content
+/// type but still replaces the browser's stored Cache-Control.
+async fn cache_control(req: Request, next: Next) -> Response {
+    let policy = cache_policy(req.uri().path());
+    let mut res = next.run(req).await;
+    if !res.status().is_success() && res.status() != StatusCode::NOT_MODIFIED {
+        return res;
+    }
+    res.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static(policy));
+    res
+}
+
+fn cache_policy(path: &str) -> &'static str {
+    if path.starts_with("/assets/") {
+        IMMUTABLE
+    } else if !looks_like_file(path) || path.ends_with(".html") {
+        // `/`, `/index.html` and client-side routes, which get index.html.
+        NO_CACHE
+    } else {
+        SHORT_CACHE
+    }
+}
+
+#[cfg(not(feature = "embed-frontend"))]
+fn embedded_router() -> Option<Router> {
+    None
+}
+
+#[cfg(feature = "embed-frontend")]
+fn embedded_router() -> Option<Router> {
+    Some(Router::new().fallback(embedded::serve))
+}
+
+#[cfg(feature = "embed-frontend")]
+mod embedded {
+    use axum::http::{header, HeaderMap, StatusCode, Uri};
+    use axum::response::{IntoResponse, Response};
+    use rust_embed::RustEmbed;
+
+    #[derive(RustEmbed)]
+    #[folder = "$CARGO_MANIFEST_DIR/../frontend/dist"]
+    #[allow_missing = true]
+    struct Assets;
+
+    pub async fn serve(uri: Uri, headers: HeaderMap) -> Response {
+        let path = uri.path().trim_start_matches('/');
+        let file = match Assets::get(path) {
+            Some(file) if !path.is_empty() => file,
+            _ if super::looks_like_file(uri.path()) => {
+                return StatusCode::NOT_FOUND.into_response()
+            }
+            _ => match Assets::get("index.html") {
+                Some(file) => file,
+                None => return StatusCode::NOT_FOUND.into_response(),
+            },
+        };
+
+        let etag = format!("\"{}\"", hex(&file.metadata.sha256_hash()));
+        let matches = headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v == etag);
+        if matches {
+            return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+        }
+
+        (
+            [
+                (header::CONTENT_TYPE, file.metadata.mimetype().to_string()),
+                (header::ETAG, etag),
+            ],
+            file.data,
+        )
+            .into_response()
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::HeaderMap;
+    use tower::ServiceExt;
+
+    /// Answers like the embedded server: a bare 304 when the etag matches.
+    async fn etagged(headers: HeaderMap) -> Response {
+        if headers.contains_key(header::IF_NONE_MATCH) {
+            return (StatusCode::NOT_MODIFIED, [(header::ETAG, "\"v1\"")]).into_response();
+        }
+        ([(header::CONTENT_TYPE, "text/html")], "<!doctype html>").into_response()
+    }
+
+    #[tokio::test]
+    async fn revalidations_keep_the_path_policy() {
+        let app = Router::new()
+            .fallback(etagged)
+            .layer(middleware::from_fn(cache_control));
+        for (path, policy) in [
+            ("/", NO_CACHE),
+            ("/index.html", NO_CACHE),
+            ("/lobby/abc", NO_CACHE),
+            ("/assets/app-1234.js", IMMUTABLE),
+            ("/favicon.ico", SHORT_CACHE),
+        ] {
+            let req = Request::builder()
+                .uri(path)
+                .header(header::IF_NONE_MATCH, "\"v1\"")
+                .body(Body::empty())
+                .unwrap();
+            let res = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::NOT_MODIFIED, "{path}");
+            assert_eq!(res.headers()[header::CACHE_CONTROL], policy, "{path}");
+        }
+    }
+}
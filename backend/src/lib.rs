@@ -1,73 +1,187 @@
+mod codec;
+pub mod config;
+mod delta;
+mod frontend;
+mod metrics;
+mod openapi;
+mod presence;
+mod protocol;
+mod results;
+pub mod typescript;
+
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use std::env;
+use std::time::{Duration, Instant};
 
 use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::handler::Handler;
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
 use axum::response::IntoResponse;
-use axum::routing::{get, post};
+use axum::routing::{on, MethodFilter, MethodRouter};
 use axum::{Json, Router};
-use game_core::{Game, GameEvent, GamePhase, Gift as CoreGift, GiftState, Player as CorePlayer, PlayerAction};
+use game_core::{Game, GameEvent, GamePhase, GameStats, Gift as CoreGift, GiftState, Player as CorePlayer, PlayerAction, Rules, StrategyKind, Timeline};
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
+use ts_rs::TS;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade};
 use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use futures::stream::{SplitSink, SplitStream};
 use futures::StreamExt;
 use futures::SinkExt;
+use tower_http::trace::TraceLayer;
+use tracing::field::Empty;
+use tracing::{info, warn, Instrument};
+
+use codec::{DecodeError, Encoding};
+pub use config::Config;
+use delta::{DeltaEncoder, PatchOp};
+use metrics::Metrics;
+use presence::Presence;
+use protocol::{HandshakeError, VersionInfo, PROTOCOL_VERSION};
+use results::{GameResults, ResultsFormat};
 
 #[derive(Clone)]
 pub struct AppState {
     games: Arc<RwLock<HashMap<String, GameRecord>>>,
     channels: Arc<RwLock<HashMap<String, broadcast::Sender<ServerMessage>>>>,
-    persist_path: Option<PathBuf>,
+    metrics: Arc<Metrics>,
+    presence: Arc<Presence>,
+    config: Arc<Config>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+impl AppState {
+    fn new(config: Config) -> Self {
         Self {
             games: Arc::new(RwLock::new(HashMap::new())),
             channels: Arc::new(RwLock::new(HashMap::new())),
-            persist_path: None,
+            metrics: Arc::new(Metrics::new()),
+            presence: Arc::new(Presence::default()),
+            config: Arc::new(config),
         }
     }
-}
 
-impl AppState {
-    pub async fn with_persistence(path: impl Into<PathBuf>) -> Self {
-        let path = path.into();
-        let mut state = Self::default();
-        state.persist_path = Some(path.clone());
-        if let Ok(bytes) = tokio::fs::read(&path).await {
-            if let Ok(saved) = serde_json::from_slice::<HashMap<String, GameRecord>>(&bytes) {
-                let mut games = state.games.write().await;
-                *games = saved;
-                let mut channels = state.channels.write().await;
-                for game_id in games.keys() {
-                    let (tx, _) = broadcast::channel(32);
-                    channels.insert(game_id.clone(), tx);
+    /// Build state from a resolved config, restoring saved games when
+    /// persistence is enabled.
+    pub async fn from_config(config: Config) -> Self {
+        let state = Self::new(config);
+        if let Some(path) = &state.config.persist_path {
+            if let Ok(bytes) = tokio::fs::read(path).await {
+                if let Ok(saved) = serde_json::from_slice::<HashMap<String, GameRecord>>(&bytes) {
+                    let mut games = state.games.write().await;
+                    *games = saved;
+                    let mut channels = state.channels.write().await;
+                    for game_id in games.keys() {
+                        channels.insert(game_id.clone(), state.new_channel());
+                    }
                 }
             }
         }
+        // A bot may have been up when the server stopped; nothing else
+        // would wake it.
+        let in_progress: Vec<String> = state
+            .games
+            .read()
+            .await
+            .values()
+            .filter(|g| g.phase == GamePhase::InProgress)
+            .map(|g| g.id.clone())
+            .collect();
+        for game_id in in_progress {
+            spawn_bots(&state, &game_id);
+        }
         state
     }
 
+    pub async fn with_persistence(path: impl Into<std::path::PathBuf>) -> Self {
+        Self::from_config(Config {
+            persist_path: Some(path.into()),
+            ..Config::default()
+        })
+        .await
+    }
+
+    fn new_channel(&self) -> broadcast::Sender<ServerMessage> {
+        let (tx, _) = broadcast::channel(self.config.broadcast_capacity);
+        tx
+    }
+
+    async fn channel(&self, game_id: &str) -> broadcast::Sender<ServerMessage> {
+        let mut channels = self.channels.write().await;
+        channels
+            .entry(game_id.to_string())
+            .or_insert_with(|| self.new_channel())
+            .clone()
+    }
+
+    fn view(&self, game: &GameRecord) -> GameView {
+        let online = self.presence.online_players(&game.id);
+        GameView {
+            id: game.id.clone(),
+            phase: game.phase.clone(),
+            players: game
+                .players
+                .iter()
+                .map(|p| PlayerView {
+                    id: p.id.clone(),
+                    name: p.name.clone(),
+                    joined_at: p.joined_at,
+                    online: online.contains(&p.id),
+                })
+                .collect(),
+            gifts: game.gifts.clone(),
+            turn_order: game.turn_order.clone(),
+            current_turn: game.current_turn,
+            active_player: game.active_player.clone(),
+            history: game.history.clone(),
+            rules: game.rules.clone(),
+            spectators: self.presence.spectators(&game.id),
+        }
+    }
+
+    /// Probe that the persistence directory accepts writes without touching
+    /// the state file itself. `None` when persistence is disabled.
+    async fn persistence_writable(&self) -> Option<bool> {
+        let path = self.config.persist_path.as_ref()?;
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => std::path::PathBuf::from("."),
+        };
+        let probe = dir.join(format!(".ready-{}", Uuid::new_v4()));
+        let writable = tokio::fs::write(&probe, b"ok").await.is_ok();
+        let _ = tokio::fs::remove_file(&probe).await;
+        Some(writable)
+    }
+
     async fn persist(&self) {
-        if let Some(path) = &self.persist_path {
+        if let Some(path) = &self.config.persist_path {
             let snapshot = {
                 let games = self.games.read().await;
                 games.clone()
             };
+            let started = Instant::now();
             if let Ok(json) = serde_json::to_vec_pretty(&snapshot) {
                 if let Err(err) = tokio::fs::write(path, json).await {
-                    eprintln!("persist error: {err}");
+                    tracing::error!(error = %err, path = %path.display(), "persist failed");
                 }
             }
+            self.metrics
+                .persist_seconds
+                .observe(started.elapsed().as_secs_f64());
         }
     }
 }
@@ -76,6 +190,9 @@ impl AppState {
 pub struct GameRecord {
     pub id: String,
     pub host_token: String,
+    /// Grants read-only access to the spectator socket.
+    #[serde(default = "new_token")]
+    pub spectator_token: String,
     pub players: Vec<PlayerRecord>,
     pub gifts: Vec<GiftRecord>,
     pub phase: GamePhase,
@@ -83,6 +200,8 @@ pub struct GameRecord {
     pub current_turn: usize,
     pub active_player: Option<String>,
     pub history: Vec<GameEvent>,
+    #[serde(default)]
+    pub rules: Rules,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -90,9 +209,12 @@ pub struct PlayerRecord {
     pub id: String,
     pub name: String,
     pub joined_at: u64,
+    /// Set for players added by the host that move on their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot: Option<StrategyKind>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, TS)]
 pub struct GiftRecord {
     pub id: String,
     pub submitted_by: String,
@@ -106,42 +228,107 @@ pub struct GiftRecord {
     pub state: GiftState,
 }
 
+/// One HTTP route. `app()` mounts these, and the OpenAPI test checks the
+/// spec against the same list.
+pub(crate) struct Route {
+    pub(crate) method: Method,
+    pub(crate) path: &'static str,
+    handler: Box<dyn FnOnce(MethodFilter) -> MethodRouter<AppState>>,
+}
+
+impl Route {
+    fn mount(self, router: Router<AppState>) -> Router<AppState> {
+        let filter = MethodFilter::try_from(self.method).expect("routes use standard methods");
+        router.route(self.path, (self.handler)(filter))
+    }
+}
+
+fn route<H, T>(method: Method, path: &'static str, handler: H) -> Route
+where
+    H: Handler<T, AppState>,
+    T: 'static,
+{
+    Route {
+        method,
+        path,
+        handler: Box::new(move |filter| on(filter, handler)),
+    }
+}
+
+pub(crate) fn routes() -> Vec<Route> {
+    vec![
+        route(Method::POST, "/game", create_game),
+        route(Method::POST, "/game/:id/join", join_game),
+        route(Method::POST, "/game/:id/bots", add_bot),
+        route(Method::POST, "/game/:id/gift", submit_gift),
+        route(Method::POST, "/game/:id/start", start_game),
+        route(Method::POST, "/game/:id/action", post_action),
+        route(Method::GET, "/game/:id/events", events_handler),
+        route(Method::GET, "/ws/:id/spectate", spectate_handler),
+        route(Method::GET, "/ws/:id/:player_id", ws_handler),
+        route(Method::GET, "/game/:id", get_game),
+        route(Method::GET, "/game/:id/results", get_results),
+        route(Method::GET, "/game/:id/stats", get_stats),
+        route(Method::GET, "/game/:id/timeline", get_timeline),
+        route(Method::GET, "/version", version),
+        route(Method::GET, "/healthz", healthz),
+        route(Method::GET, "/readyz", readyz),
+        route(Method::GET, "/metrics", metrics_handler),
+        route(Method::GET, "/openapi.json", openapi::openapi_json),
+    ]
+}
+
 pub fn app(state: AppState) -> Router {
-    Router::new()
-        .route("/game", post(create_game))
-        .route("/game/:id/join", post(join_game))
-        .route("/game/:id/gift", post(submit_gift))
-        .route("/game/:id/start", post(start_game))
-        .route("/ws/:id/:player_id", get(ws_handler))
-        .route("/game/:id", get(get_game))
-        .with_state(state)
+    let router = routes()
+        .into_iter()
+        .fold(Router::new(), |router, route| route.mount(router));
+    let router = match frontend::router(state.config.static_dir.as_deref()) {
+        Some(frontend) => router.fallback_service(frontend),
+        None => router,
+    };
+    router.layer(TraceLayer::new_for_http()).with_state(state)
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct CreateGameResponse {
     game_id: String,
     host_token: String,
+    spectator_token: String,
 }
 
-fn admin_password() -> String {
-    env::var("ADMIN_PASSWORD").unwrap_or_else(|_| "changeme".to_string())
+fn new_token() -> String {
+    Uuid::new_v4().to_string()
 }
 
+#[utoipa::path(
+    post,
+    path = "/game",
+    params(("x-admin-password" = String, Header, description = "Server admin password")),
+    responses(
+        (status = 201, body = CreateGameResponse),
+        (status = 401, description = "invalid admin password"),
+        (status = 503, description = "game limit reached"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = Empty))]
 async fn create_game(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
-    let expected = admin_password();
     let provided = headers
         .get("x-admin-password")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("");
-    if provided != expected {
+    if provided != state.config.admin_password {
+        warn!("create rejected: invalid admin password");
         return (StatusCode::UNAUTHORIZED, "invalid admin password").into_response();
     }
 
     let game_id = Uuid::new_v4().to_string();
-    let host_token = Uuid::new_v4().to_string();
+    tracing::Span::current().record("game_id", game_id.as_str());
+    let host_token = new_token();
+    let spectator_token = new_token();
     let record = GameRecord {
         id: game_id.clone(),
         host_token: host_token.clone(),
+        spectator_token: spectator_token.clone(),
         players: Vec::new(),
         gifts: Vec::new(),
         phase: GamePhase::Submissions,
@@ -149,11 +336,24 @@ async fn create_game(State(state): State<AppState>, headers: HeaderMap) -> impl
         current_turn: 0,
         active_player: None,
         history: Vec::new(),
+        rules: state.config.rules.clone(),
     };
 
-    state.games.write().await.insert(game_id.clone(), record);
-    let (tx, _) = broadcast::channel(32);
-    state.channels.write().await.insert(game_id.clone(), tx);
+    {
+        let mut games = state.games.write().await;
+        if games.len() >= state.config.limits.max_games {
+            warn!(max_games = state.config.limits.max_games, "create rejected: game limit reached");
+            return (StatusCode::SERVICE_UNAVAILABLE, "game limit reached").into_response();
+        }
+        games.insert(game_id.clone(), record);
+    }
+    state
+        .channels
+        .write()
+        .await
+        .insert(game_id.clone(), state.new_channel());
+    state.metrics.games_created.inc();
+    info!("game created");
     state.persist().await;
 
     (
@@ -161,22 +361,23 @@ async fn create_game(State(state): State<AppState>, headers: HeaderMap) -> impl
         Json(CreateGameResponse {
             game_id,
             host_token,
+            spectator_token,
         }),
     )
         .into_response()
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct JoinRequest {
     name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct JoinResponse {
     player_id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct GiftRequest {
     player_id: String,
     product_url: String,
@@ -185,36 +386,115 @@ struct GiftRequest {
     title: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct GiftResponse {
     gift: GiftRecord,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
+struct BotRequest {
+    name: String,
+    strategy: StrategyKind,
+    /// The gift the bot brings; bots need one like everybody else.
+    product_url: String,
+    hint: String,
+    image_url: Option<String>,
+    title: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct BotResponse {
+    player_id: String,
+    gift: GiftRecord,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct StartParams {
     seed: Option<u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct StartResponse {
     phase: GamePhase,
     turn_order: Vec<String>,
     active_player: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum ServerMessage {
     State(GameView),
-    Event(GameEvent),
+    Event { event: GameEvent },
+    /// Sent once, right after `GameFinished`.
+    Stats(GameStats),
+    Spectators { count: usize },
+    /// A player's first socket opened or last socket closed.
+    PresenceChanged { player_id: String, online: bool },
+    /// Reply to the client's hello with the features both sides support.
+    Welcome {
+        protocol_version: u32,
+        features: Vec<String>,
+    },
+    /// Delta mode: full state that following patches build on.
+    Snapshot {
+        #[ts(type = "number")]
+        seq: u64,
+        state: GameView,
+    },
+    /// Delta mode: turns the state numbered `base` into state `seq`.
+    Patch {
+        #[ts(type = "number")]
+        base: u64,
+        #[ts(type = "number")]
+        seq: u64,
+        ops: Vec<PatchOp>,
+    },
 }
 
-#[derive(Deserialize)]
+impl ServerMessage {
+    /// Spectator copy of the message with unopened gift details hidden.
+    fn redacted(self) -> Self {
+        match self {
+            ServerMessage::State(view) => ServerMessage::State(view.redacted()),
+            ServerMessage::Snapshot { seq, state } => ServerMessage::Snapshot {
+                seq,
+                state: state.redacted(),
+            },
+            other => other,
+        }
+    }
+}
+
+/// A message a client sends over the game socket.
+#[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
-enum ClientMessage {
+pub enum ClientMessage {
+    /// Must be the first message on a socket.
+    Hello {
+        protocol_version: u32,
+        #[serde(default)]
+        #[ts(as = "Option<Vec<String>>", optional)]
+        features: Vec<String>,
+    },
     Action(PlayerAction),
+    /// Ask for a full snapshot, e.g. after a patch arrived out of sequence.
+    Resync,
 }
 
+#[utoipa::path(
+    post,
+    path = "/game/{id}/join",
+    params(("id" = String, Path, description = "Game id")),
+    request_body = JoinRequest,
+    responses(
+        (status = 200, body = JoinResponse),
+        (status = 400, description = "name required"),
+        (status = 404, description = "game not found"),
+        (status = 409, description = "name taken or lobby full"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id, player_id = Empty))]
 async fn join_game(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
@@ -231,8 +511,33 @@ async fn join_game(
         None => return (StatusCode::NOT_FOUND, "game not found").into_response(),
     };
 
+    let player_id = match seat_player(game, name, state.config.limits.max_players, None) {
+        Ok(player_id) => player_id,
+        Err(rejection) => return rejection.into_response(),
+    };
+    tracing::Span::current().record("player_id", player_id.as_str());
+
+    drop(games);
+    state.metrics.players_joined.inc();
+    info!(name, "player joined");
+    state.persist().await;
+
+    (StatusCode::OK, Json(JoinResponse { player_id })).into_response()
+}
+
+/// Add a player to `game`, checking the name and the lobby size.
+fn seat_player(
+    game: &mut GameRecord,
+    name: &str,
+    max_players: usize,
+    bot: Option<StrategyKind>,
+) -> Result<String, (StatusCode, &'static str)> {
     if game.players.iter().any(|p| p.name == name) {
-        return (StatusCode::CONFLICT, "name taken").into_response();
+        return Err((StatusCode::CONFLICT, "name taken"));
+    }
+
+    if game.players.len() >= max_players {
+        return Err((StatusCode::CONFLICT, "lobby full"));
     }
 
     let player_id = Uuid::new_v4().to_string();
@@ -245,14 +550,96 @@ async fn join_game(
         id: player_id.clone(),
         name: name.to_string(),
         joined_at: now,
+        bot,
     });
+    Ok(player_id)
+}
+
+#[utoipa::path(
+    post,
+    path = "/game/{id}/bots",
+    params(
+        ("id" = String, Path, description = "Game id"),
+        ("x-host-token" = String, Header, description = "Host token from game creation"),
+    ),
+    request_body = BotRequest,
+    responses(
+        (status = 200, body = BotResponse),
+        (status = 400, description = "name, product_url and hint required"),
+        (status = 401, description = "missing or invalid host token"),
+        (status = 404, description = "game not found"),
+        (status = 409, description = "submissions closed, name taken or lobby full"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id, player_id = Empty))]
+async fn add_bot(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<BotRequest>,
+) -> impl IntoResponse {
+    let name = payload.name.trim();
+    if name.is_empty() || payload.product_url.trim().is_empty() || payload.hint.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "name, product_url and hint required").into_response();
+    }
+
+    let mut games = state.games.write().await;
+    let game = match games.get_mut(&game_id) {
+        Some(game) => game,
+        None => return (StatusCode::NOT_FOUND, "game not found").into_response(),
+    };
+
+    if let Err(rejection) = require_host(&headers, game) {
+        return rejection.into_response();
+    }
+
+    if !matches!(game.phase, GamePhase::Submissions) {
+        return (StatusCode::CONFLICT, "submissions closed").into_response();
+    }
+
+    let max_players = state.config.limits.max_players;
+    let player_id = match seat_player(game, name, max_players, Some(payload.strategy)) {
+        Ok(player_id) => player_id,
+        Err(rejection) => return rejection.into_response(),
+    };
+    tracing::Span::current().record("player_id", player_id.as_str());
+
+    let gift = GiftRecord {
+        id: Uuid::new_v4().to_string(),
+        submitted_by: player_id.clone(),
+        product_url: payload.product_url,
+        hint: payload.hint,
+        image_url: payload.image_url,
+        title: payload.title,
+        opened_by: None,
+        held_by: None,
+        stolen_count: 0,
+        state: GiftState::Unopened,
+    };
+    game.gifts.push(gift.clone());
 
     drop(games);
+    state.metrics.players_joined.inc();
+    state.metrics.gifts_submitted.inc();
+    info!(name, strategy = payload.strategy.name(), "bot added");
     state.persist().await;
 
-    (StatusCode::OK, Json(JoinResponse { player_id })).into_response()
+    (StatusCode::OK, Json(BotResponse { player_id, gift })).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/game/{id}/gift",
+    params(("id" = String, Path, description = "Game id")),
+    request_body = GiftRequest,
+    responses(
+        (status = 200, body = GiftResponse),
+        (status = 400, description = "product_url and hint required"),
+        (status = 404, description = "game or player not found"),
+        (status = 409, description = "submissions closed"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id, player_id = %payload.player_id))]
 async fn submit_gift(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
@@ -305,21 +692,75 @@ async fn submit_gift(
     };
 
     drop(games);
+    state.metrics.gifts_submitted.inc();
+    info!(gift_id = %gift_record.id, "gift submitted");
     state.persist().await;
 
     (StatusCode::OK, Json(GiftResponse { gift: gift_record })).into_response()
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, TS)]
 struct GameView {
     id: String,
     phase: GamePhase,
-    players: Vec<PlayerRecord>,
+    players: Vec<PlayerView>,
     gifts: Vec<GiftRecord>,
     turn_order: Vec<String>,
+    /// Together with `history` and `rules`, lets a client feed this view
+    /// straight into the game-wasm rules to preview moves.
+    #[serde(default)]
+    current_turn: usize,
     active_player: Option<String>,
+    /// Grows with the game, but is bounded: one open and at most
+    /// `max_steals_per_gift` steals per gift, each with its turn change, so
+    /// about 250 events at the default 30 players. Delta connections are sent
+    /// only each move's new events.
+    #[serde(default)]
+    history: Vec<GameEvent>,
+    #[serde(default)]
+    rules: Rules,
+    #[serde(default)]
+    spectators: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, TS)]
+struct PlayerView {
+    id: String,
+    name: String,
+    #[ts(type = "number")]
+    joined_at: u64,
+    /// Whether the player has at least one live socket.
+    online: bool,
+}
+
+impl GameView {
+    /// Hide what is inside (and who brought) gifts that are still wrapped, so
+    /// the big screen cannot spoil them.
+    fn redacted(mut self) -> Self {
+        for gift in self
+            .gifts
+            .iter_mut()
+            .filter(|g| matches!(g.state, GiftState::Unopened))
+        {
+            gift.submitted_by.clear();
+            gift.product_url.clear();
+            gift.image_url = None;
+            gift.title = None;
+        }
+        self
+    }
 }
 
+#[utoipa::path(
+    get,
+    path = "/game/{id}",
+    params(("id" = String, Path, description = "Game id")),
+    responses(
+        (status = 200, body = GameView),
+        (status = 404, description = "game not found"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
 async fn get_game(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
@@ -329,20 +770,225 @@ async fn get_game(
         return (StatusCode::NOT_FOUND, "game not found").into_response();
     };
 
+    (StatusCode::OK, Json(state.view(game))).into_response()
+}
+
+#[utoipa::path(get, path = "/version", responses((status = 200, body = VersionInfo)))]
+async fn version() -> impl IntoResponse {
+    Json(VersionInfo::current())
+}
+
+#[utoipa::path(get, path = "/healthz", responses((status = 200, description = "process is up")))]
+async fn healthz() -> impl IntoResponse {
+    (StatusCode::OK, "ok")
+}
+
+#[derive(Serialize, ToSchema)]
+struct ReadyResponse {
+    status: &'static str,
+    version: &'static str,
+    games: usize,
+    connected_sockets: usize,
+    persistence: &'static str,
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, body = ReadyResponse),
+        (status = 503, body = ReadyResponse, description = "persistence unwritable"),
+    )
+)]
+async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let (ready, persistence) = match state.persistence_writable().await {
+        None => (true, "disabled"),
+        Some(true) => (true, "ok"),
+        Some(false) => (false, "unwritable"),
+    };
+    let games = state.games.read().await.len();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
     (
-        StatusCode::OK,
-        Json(GameView {
-            id: game.id.clone(),
-            phase: game.phase.clone(),
-            players: game.players.clone(),
-            gifts: game.gifts.clone(),
-            turn_order: game.turn_order.clone(),
-            active_player: game.active_player.clone(),
+        status,
+        Json(ReadyResponse {
+            status: if ready { "ready" } else { "unavailable" },
+            version: env!("CARGO_PKG_VERSION"),
+            games,
+            connected_sockets: state.metrics.ws_connections.get().max(0) as usize,
+            persistence,
         }),
     )
-        .into_response()
 }
 
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Prometheus text exposition", content_type = "text/plain"))
+)]
+async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
+/// Check the `x-host-token` header against the game's host token.
+fn require_host(headers: &HeaderMap, game: &GameRecord) -> Result<(), (StatusCode, &'static str)> {
+    let Some(token_val) = headers.get("x-host-token").and_then(|v| v.to_str().ok()) else {
+        return Err((StatusCode::UNAUTHORIZED, "host token required"));
+    };
+
+    if token_val != game.host_token {
+        warn!("rejected: invalid host token");
+        return Err((StatusCode::UNAUTHORIZED, "invalid host token"));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ResultsParams {
+    #[serde(default)]
+    format: ResultsFormat,
+}
+
+#[utoipa::path(
+    get,
+    path = "/game/{id}/results",
+    params(
+        ("id" = String, Path, description = "Game id"),
+        ("x-host-token" = String, Header, description = "Host token from game creation"),
+        ResultsParams,
+    ),
+    responses(
+        (status = 200, body = GameResults, description = "JSON, CSV or Markdown depending on `format`"),
+        (status = 401, description = "missing or invalid host token"),
+        (status = 404, description = "game not found"),
+        (status = 409, description = "game not finished"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
+async fn get_results(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    headers: HeaderMap,
+    Query(params): Query<ResultsParams>,
+) -> impl IntoResponse {
+    let games = state.games.read().await;
+    let Some(game) = games.get(&game_id) else {
+        return (StatusCode::NOT_FOUND, "game not found").into_response();
+    };
+
+    if let Err(rejection) = require_host(&headers, game) {
+        return rejection.into_response();
+    }
+
+    if !matches!(game.phase, GamePhase::Finished) {
+        return (StatusCode::CONFLICT, "game not finished").into_response();
+    }
+
+    let results = GameResults::from_record(game);
+    drop(games);
+
+    let disposition = |ext: &str| format!("attachment; filename=\"results-{game_id}.{ext}\"");
+    match params.format {
+        ResultsFormat::Json => (StatusCode::OK, Json(results)).into_response(),
+        ResultsFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, disposition("csv")),
+            ],
+            results.to_csv(),
+        )
+            .into_response(),
+        ResultsFormat::Md => (
+            [
+                (header::CONTENT_TYPE, "text/markdown; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, disposition("md")),
+            ],
+            results.to_markdown(),
+        )
+            .into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/game/{id}/stats",
+    params(("id" = String, Path, description = "Game id")),
+    responses(
+        (status = 200, body = GameStats),
+        (status = 404, description = "game not found"),
+        (status = 409, description = "game not finished"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
+async fn get_stats(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> impl IntoResponse {
+    let games = state.games.read().await;
+    let Some(game) = games.get(&game_id) else {
+        return (StatusCode::NOT_FOUND, "game not found").into_response();
+    };
+
+    if !matches!(game.phase, GamePhase::Finished) {
+        return (StatusCode::CONFLICT, "game not finished").into_response();
+    }
+
+    (StatusCode::OK, Json(GameStats::from_history(&game.history))).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/game/{id}/timeline",
+    params(("id" = String, Path, description = "Game id")),
+    responses(
+        (status = 200, body = Timeline),
+        (status = 404, description = "game not found"),
+        (status = 409, description = "game not finished"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
+async fn get_timeline(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> impl IntoResponse {
+    let games = state.games.read().await;
+    let Some(game) = games.get(&game_id) else {
+        return (StatusCode::NOT_FOUND, "game not found").into_response();
+    };
+
+    if !matches!(game.phase, GamePhase::Finished) {
+        return (StatusCode::CONFLICT, "game not finished").into_response();
+    }
+
+    let timeline = Timeline::from_history(&game.turn_order, &game.history);
+    (StatusCode::OK, Json(timeline)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/game/{id}/start",
+    params(
+        ("id" = String, Path, description = "Game id"),
+        ("x-host-token" = String, Header, description = "Host token from game creation"),
+        StartParams,
+    ),
+    responses(
+        (status = 200, body = StartResponse),
+        (status = 400, description = "no players, or a player has not submitted a gift"),
+        (status = 401, description = "missing or invalid host token"),
+        (status = 404, description = "game not found"),
+        (status = 409, description = "game already started"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
 async fn start_game(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
@@ -355,12 +1001,8 @@ async fn start_game(
         None => return (StatusCode::NOT_FOUND, "game not found").into_response(),
     };
 
-    let Some(token_val) = headers.get("x-host-token").and_then(|v| v.to_str().ok()) else {
-        return (StatusCode::UNAUTHORIZED, "host token required").into_response();
-    };
-
-    if token_val != game.host_token {
-        return (StatusCode::UNAUTHORIZED, "invalid host token").into_response();
+    if let Err(rejection) = require_host(&headers, game) {
+        return rejection.into_response();
     }
 
     if !matches!(game.phase, GamePhase::Submissions) {
@@ -384,7 +1026,7 @@ async fn start_game(
     let mut rng = params
         .seed
         .map(ChaCha8Rng::seed_from_u64)
-        .unwrap_or_else(|| ChaCha8Rng::from_entropy());
+        .unwrap_or_else(ChaCha8Rng::from_entropy);
     turn_order.shuffle(&mut rng);
 
     game.phase = GamePhase::InProgress;
@@ -410,29 +1052,206 @@ async fn start_game(
         .into_response();
 
     drop(games);
+    info!("game started");
     state.persist().await;
+    spawn_bots(&state, &game_id);
 
     response
 }
 
+#[derive(Deserialize)]
+struct SocketParams {
+    /// Send states as patches instead of full views.
+    #[serde(default)]
+    delta: bool,
+    /// Overrides any encoding picked by subprotocol.
+    encoding: Option<Encoding>,
+}
+
 async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
     Path((game_id, player_id)): Path<(String, String)>,
+    Query(params): Query<SocketParams>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state, game_id, player_id))
+    ws.protocols(Encoding::SUBPROTOCOLS)
+        .on_upgrade(move |socket| handle_socket(socket, state, game_id, player_id, params))
+}
+
+/// Query flag first, then the negotiated subprotocol, then JSON.
+fn negotiated_encoding(socket: &WebSocket, requested: Option<Encoding>) -> Encoding {
+    requested
+        .or_else(|| {
+            socket
+                .protocol()
+                .and_then(|p| p.to_str().ok())
+                .and_then(Encoding::from_subprotocol)
+        })
+        .unwrap_or_default()
 }
 
-async fn handle_socket(stream: WebSocket, state: AppState, game_id: String, player_id: String) {
-    let (sender, mut receiver) = stream.split();
-    let sender = Arc::new(tokio::sync::Mutex::new(sender));
+/// Decode a client frame in whatever encoding it arrived in. Text frames
+/// are always JSON; binary frames need a binary encoding to be negotiated.
+fn decode_client_frame(
+    encoding: Encoding,
+    msg: &Message,
+) -> Option<Result<ClientMessage, DecodeError>> {
+    match msg {
+        Message::Text(text) => Some(serde_json::from_str(text).map_err(DecodeError::from)),
+        Message::Binary(bytes) if encoding.is_binary() => Some(encoding.decode(bytes)),
+        _ => None,
+    }
+}
 
-    // Fetch game and channel
-    let snapshot = {
+/// How one connection wants its messages shaped.
+struct Feed {
+    /// Hide unopened gift details (spectators).
+    redact: bool,
+    delta: Option<DeltaEncoder>,
+}
+
+impl Feed {
+    fn new(redact: bool, delta: bool) -> Self {
+        Self {
+            redact,
+            delta: delta.then(DeltaEncoder::default),
+        }
+    }
+
+    fn prepare(&mut self, msg: ServerMessage) -> ServerMessage {
+        let msg = if self.redact { msg.redacted() } else { msg };
+        match (msg, self.delta.as_mut()) {
+            (ServerMessage::State(view), Some(delta)) => delta.encode(view),
+            (msg, _) => msg,
+        }
+    }
+
+    fn resync(&mut self) {
+        if let Some(delta) = self.delta.as_mut() {
+            delta.resync();
+        }
+    }
+
+    fn enable_delta(&mut self) {
+        self.delta.get_or_insert_with(DeltaEncoder::default);
+    }
+}
+
+/// Outgoing half of a socket. Sharing one lock for the sink and the feed
+/// keeps patches in the order their sequence numbers were handed out.
+struct Outbox {
+    sink: SplitSink<WebSocket, Message>,
+    feed: Feed,
+    encoding: Encoding,
+}
+
+impl Outbox {
+    async fn send(&mut self, msg: Message) -> Result<(), axum::Error> {
+        self.sink.send(msg).await
+    }
+
+    async fn push(&mut self, msg: ServerMessage) -> Result<(), axum::Error> {
+        let msg = self.feed.prepare(msg);
+        let frame = self.encoding.encode(&msg);
+        self.send(frame).await
+    }
+}
+
+type WsSender = Arc<tokio::sync::Mutex<Outbox>>;
+
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait for the client's hello and answer it with a welcome. A client that
+/// skips the hello or speaks another protocol version gets an error and a
+/// close frame saying why.
+async fn handshake(
+    receiver: &mut SplitStream<WebSocket>,
+    sender: &WsSender,
+) -> Result<Vec<String>, HandshakeError> {
+    let encoding = sender.lock().await.encoding;
+    let hello = tokio::time::timeout(HELLO_TIMEOUT, async {
+        while let Some(Ok(msg)) = receiver.next().await {
+            match msg {
+                Message::Ping(_) | Message::Pong(_) => continue,
+                msg => return decode_client_frame(encoding, &msg),
+            }
+        }
+        None
+    })
+    .await;
+    let result = match hello {
+        Err(_) => Err(HandshakeError::Timeout),
+        Ok(Some(Ok(ClientMessage::Hello {
+            protocol_version,
+            features,
+        }))) => protocol::negotiate(protocol_version, &features),
+        Ok(_) => Err(HandshakeError::MissingHello),
+    };
+
+    let mut outbox = sender.lock().await;
+    match &result {
+        Ok(features) => {
+            if features.iter().any(|f| f == "delta") {
+                outbox.feed.enable_delta();
+            }
+            let _ = outbox
+                .push(ServerMessage::Welcome {
+                    protocol_version: PROTOCOL_VERSION,
+                    features: features.clone(),
+                })
+                .await;
+        }
+        Err(err) => {
+            warn!(error = %err, "handshake failed");
+            let _ = outbox.send(Message::Text(format!("error:{err:?}"))).await;
+            let _ = outbox
+                .send(Message::Close(Some(CloseFrame {
+                    code: close_code::PROTOCOL,
+                    reason: err.to_string().into(),
+                })))
+                .await;
+        }
+    }
+    result
+}
+
+/// Send the current state in full, restarting delta sequencing.
+async fn resync(state: &AppState, game_id: &str, sender: &WsSender) {
+    let view = {
+        let games = state.games.read().await;
+        let Some(game) = games.get(game_id) else {
+            return;
+        };
+        state.view(game)
+    };
+    let mut outbox = sender.lock().await;
+    outbox.feed.resync();
+    let _ = outbox.push(ServerMessage::State(view)).await;
+}
+
+#[tracing::instrument(skip_all, fields(game_id = %game_id, player_id = %player_id))]
+async fn handle_socket(
+    stream: WebSocket,
+    state: AppState,
+    game_id: String,
+    player_id: String,
+    params: SocketParams,
+) {
+    let encoding = negotiated_encoding(&stream, params.encoding);
+    let (sink, mut receiver) = stream.split();
+    let sender: WsSender = Arc::new(tokio::sync::Mutex::new(Outbox {
+        sink,
+        feed: Feed::new(false, params.delta),
+        encoding,
+    }));
+
+    // Check game and player
+    {
         let games = state.games.read().await;
         let game = match games.get(&game_id) {
-            Some(g) => g.clone(),
+            Some(g) => g,
             None => {
+                warn!("socket rejected: unknown game");
                 let _ = sender
                     .lock()
                     .await
@@ -442,6 +1261,7 @@ async fn handle_socket(stream: WebSocket, state: AppState, game_id: String, play
             }
         };
         if !game.players.iter().any(|p| p.id == player_id) {
+            warn!("socket rejected: unknown player");
             let _ = sender
                 .lock()
                 .await
@@ -449,80 +1269,534 @@ async fn handle_socket(stream: WebSocket, state: AppState, game_id: String, play
                 .await;
             return;
         }
-        GameView {
-            id: game.id.clone(),
-            phase: game.phase.clone(),
-            players: game.players.clone(),
-            gifts: game.gifts.clone(),
-            turn_order: game.turn_order.clone(),
-            active_player: game.active_player.clone(),
-        }
-    };
+    }
 
-    let rx = {
-        let mut channels = state.channels.write().await;
-        channels
-            .entry(game_id.clone())
-            .or_insert_with(|| {
-                let (tx, _) = broadcast::channel(32);
-                tx
-            })
-            .subscribe()
-    };
+    if handshake(&mut receiver, &sender).await.is_err() {
+        return;
+    }
 
-    // Send snapshot
-    let _ = sender
-        .lock()
-        .await
-        .send(Message::Text(
-            serde_json::to_string(&ServerMessage::State(snapshot)).unwrap(),
-        ))
-        .await;
+    let tx = state.channel(&game_id).await;
+    let rx = tx.subscribe();
 
-    // Task to forward broadcasts
-    let sender_clone = sender.clone();
-    let mut send_task = tokio::spawn(async move {
-        let mut rx = rx;
-        while let Ok(msg) = rx.recv().await {
-            if sender_clone
-                .lock()
-                .await
-                .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-                .await
-                .is_err()
-            {
-                break;
-            }
-        }
-    });
+    let _connection = ConnectionGuard::new(state.metrics.ws_connections.clone());
+    let _presence = PlayerGuard::new(&state, &game_id, &player_id, tx);
+    info!("socket connected");
+
+    // Snapshot taken after registering so it already shows this player online
+    resync(&state, &game_id, &sender).await;
+
+    let send_task = forward_broadcasts(rx, sender.clone(), state.metrics.clone());
+    let missed_pongs = Arc::new(AtomicU32::new(0));
+    let heartbeat_task = spawn_heartbeat(sender.clone(), missed_pongs.clone(), &state.config);
 
     let state_clone = state.clone();
     let sender_err = sender.clone();
     let recv_task = tokio::spawn(async move {
-        while let Some(Ok(Message::Text(text))) = receiver.next().await {
-            if let Ok(ClientMessage::Action(action)) = serde_json::from_str(&text) {
-                if let Err(e) =
-                    process_action(&state_clone, &game_id, &player_id, action.clone()).await
+        while let Some(Ok(msg)) = receiver.next().await {
+            // Any frame proves the client is still there.
+            missed_pongs.store(0, Ordering::Relaxed);
+            let decoded = match msg {
+                Message::Close(_) => break,
+                Message::Ping(_) | Message::Pong(_) => continue,
+                msg => decode_client_frame(encoding, &msg),
+            };
+            let Some(decoded) = decoded else {
+                let _ = sender_err
+                    .lock()
+                    .await
+                    .send(Message::Text(format!(
+                        "error:{:?}",
+                        GameActionError::UnsupportedFrame
+                    )))
+                    .await;
+                continue;
+            };
+            match decoded {
+                Ok(ClientMessage::Resync) => resync(&state_clone, &game_id, &sender_err).await,
+                Ok(ClientMessage::Hello { .. }) => tracing::debug!("ignoring repeated hello"),
+                Ok(ClientMessage::Action(action)) => {
+                    if let Err(e) =
+                        process_action(&state_clone, &game_id, &player_id, action.clone()).await
+                    {
+                        let _ = sender_err
+                            .lock()
+                            .await
+                            .send(Message::Text(format!("error:{e:?}")))
+                            .await;
+                    }
+                }
+                Err(err) => tracing::debug!(error = %err, "ignoring malformed client message"),
+            }
+        }
+    }
+    .in_current_span());
+
+    supervise([send_task, recv_task, heartbeat_task]).await;
+    info!("socket disconnected");
+}
+
+/// Wait for the first socket task to end (client gone, broadcast closed or
+/// heartbeat timed out), then abort the rest.
+async fn supervise<const N: usize>(mut tasks: [tokio::task::JoinHandle<()>; N]) {
+    let _ = futures::future::select_all(tasks.iter_mut()).await;
+    for task in &tasks {
+        task.abort();
+    }
+}
+
+/// Ping the client every interval; close the socket once too many pings go
+/// unanswered. The receive loop resets `missed` on every incoming frame.
+fn spawn_heartbeat(
+    sender: WsSender,
+    missed: Arc<AtomicU32>,
+    config: &Config,
+) -> tokio::task::JoinHandle<()> {
+    let interval = Duration::from_millis(config.ws_ping_interval_ms);
+    let max_missed = config.ws_max_missed_pongs;
+    tokio::spawn(
+        async move {
+            let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            loop {
+                ticker.tick().await;
+                if missed.fetch_add(1, Ordering::Relaxed) >= max_missed {
+                    warn!("socket missed heartbeats; closing");
+                    let _ = sender.lock().await.send(Message::Close(None)).await;
+                    break;
+                }
+                if sender
+                    .lock()
+                    .await
+                    .send(Message::Ping(Vec::new()))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+        .in_current_span(),
+    )
+}
+
+/// Forward a game's broadcasts to one socket until either side goes away.
+fn forward_broadcasts(
+    mut rx: broadcast::Receiver<ServerMessage>,
+    sender: WsSender,
+    metrics: Arc<Metrics>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(
+        async move {
+            loop {
+                let msg = match rx.recv().await {
+                    Ok(msg) => msg,
+                    // Skipped messages are superseded by the next full state.
+                    Err(RecvError::Lagged(skipped)) => {
+                        metrics.broadcast_lagged.inc();
+                        warn!(skipped, "socket lagged behind broadcast");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if sender.lock().await.push(msg).await.is_err()
                 {
-                    let _ = sender_err
-                        .lock()
-                        .await
-                        .send(Message::Text(format!("error:{e:?}")))
-                        .await;
+                    break;
+                }
+            }
+        }
+        .in_current_span(),
+    )
+}
+
+#[derive(Deserialize)]
+struct SpectateParams {
+    token: Option<String>,
+    #[serde(default)]
+    delta: bool,
+    encoding: Option<Encoding>,
+}
+
+/// Read-only socket for the projector and remote guests. Accepts either the
+/// game's spectator token or its host token.
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
+async fn spectate_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Query(params): Query<SpectateParams>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    {
+        let games = state.games.read().await;
+        let Some(game) = games.get(&game_id) else {
+            return (StatusCode::NOT_FOUND, "game not found").into_response();
+        };
+        let Some(token) = params.token.as_deref() else {
+            return (StatusCode::UNAUTHORIZED, "spectator token required").into_response();
+        };
+        if token != game.spectator_token && token != game.host_token {
+            warn!("spectator rejected: invalid token");
+            return (StatusCode::UNAUTHORIZED, "invalid spectator token").into_response();
+        }
+    }
+    ws.protocols(Encoding::SUBPROTOCOLS)
+        .on_upgrade(move |socket| handle_spectator(socket, state, game_id, params))
+}
+
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
+async fn handle_spectator(
+    stream: WebSocket,
+    state: AppState,
+    game_id: String,
+    params: SpectateParams,
+) {
+    let encoding = negotiated_encoding(&stream, params.encoding);
+    let (sink, mut receiver) = stream.split();
+    let sender: WsSender = Arc::new(tokio::sync::Mutex::new(Outbox {
+        sink,
+        feed: Feed::new(true, params.delta),
+        encoding,
+    }));
+
+    if handshake(&mut receiver, &sender).await.is_err() {
+        return;
+    }
+
+    let tx = state.channel(&game_id).await;
+    let rx = tx.subscribe();
+    let _connection = ConnectionGuard::new(state.metrics.ws_connections.clone());
+    let _spectator = SpectatorGuard::new(&state, &game_id, tx);
+    info!("spectator connected");
+
+    resync(&state, &game_id, &sender).await;
+
+    let send_task = forward_broadcasts(rx, sender.clone(), state.metrics.clone());
+    let missed_pongs = Arc::new(AtomicU32::new(0));
+    let heartbeat_task = spawn_heartbeat(sender.clone(), missed_pongs.clone(), &state.config);
+
+    let recv_task = tokio::spawn(
+        async move {
+            while let Some(Ok(msg)) = receiver.next().await {
+                missed_pongs.store(0, Ordering::Relaxed);
+                match msg {
+                    Message::Close(_) => break,
+                    Message::Ping(_) | Message::Pong(_) => {}
+                    msg if matches!(
+                        decode_client_frame(encoding, &msg),
+                        Some(Ok(ClientMessage::Resync))
+                    ) =>
+                    {
+                        resync(&state, &game_id, &sender).await;
+                    }
+                    Message::Text(_) | Message::Binary(_) => {
+                        let _ = sender
+                            .lock()
+                            .await
+                            .send(Message::Text(format!(
+                                "error:{:?}",
+                                GameActionError::ReadOnly
+                            )))
+                            .await;
+                    }
+                }
+            }
+        }
+        .in_current_span(),
+    );
+
+    supervise([send_task, recv_task, heartbeat_task]).await;
+    info!("spectator disconnected");
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct EventsParams {
+    player_id: String,
+    #[serde(default)]
+    delta: bool,
+}
+
+/// Server-sent events fallback for clients whose network breaks WebSockets.
+/// Carries the same `ServerMessage` payloads as the player socket, starting
+/// with a full state snapshot.
+#[utoipa::path(
+    get,
+    path = "/game/{id}/events",
+    params(("id" = String, Path, description = "Game id"), EventsParams),
+    responses(
+        (status = 200, description = "`ServerMessage` payloads as server-sent events", content_type = "text/event-stream"),
+        (status = 404, description = "game or player not found"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id, player_id = %params.player_id))]
+async fn events_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Query(params): Query<EventsParams>,
+) -> impl IntoResponse {
+    let player_id = params.player_id;
+    {
+        let games = state.games.read().await;
+        let Some(game) = games.get(&game_id) else {
+            return (StatusCode::NOT_FOUND, "game not found").into_response();
+        };
+        if !game.players.iter().any(|p| p.id == player_id) {
+            return (StatusCode::NOT_FOUND, "player not found").into_response();
+        }
+    }
+
+    let tx = state.channel(&game_id).await;
+    let rx = tx.subscribe();
+    // Lives inside the stream, so the player goes offline when the client
+    // disconnects and axum drops the response body.
+    let presence = PlayerGuard::new(&state, &game_id, &player_id, tx);
+    info!("event stream connected");
+
+    let snapshot = {
+        let games = state.games.read().await;
+        let Some(game) = games.get(&game_id) else {
+            return (StatusCode::NOT_FOUND, "game not found").into_response();
+        };
+        ServerMessage::State(state.view(game))
+    };
+
+    let metrics = state.metrics.clone();
+    let updates = futures::stream::unfold((rx, presence), move |(mut rx, presence)| {
+        let metrics = metrics.clone();
+        async move {
+            loop {
+                match rx.recv().await {
+                    Ok(msg) => return Some((msg, (rx, presence))),
+                    Err(RecvError::Lagged(skipped)) => {
+                        metrics.broadcast_lagged.inc();
+                        warn!(skipped, "event stream lagged behind broadcast");
+                    }
+                    Err(RecvError::Closed) => return None,
                 }
             }
         }
     });
+    // Patches only need resyncing on reconnect, which EventSource does itself.
+    let mut feed = Feed::new(false, params.delta);
+    let stream = futures::stream::once(async { snapshot })
+        .chain(updates)
+        .map(move |msg| SseEvent::default().json_data(feed.prepare(msg)));
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ActionRequest {
+    player_id: String,
+    action: PlayerAction,
+}
+
+/// REST counterpart to sending `ClientMessage::Action` over the socket.
+/// Results arrive on the event stream, so success carries no body.
+#[utoipa::path(
+    post,
+    path = "/game/{id}/action",
+    params(("id" = String, Path, description = "Game id")),
+    request_body = ActionRequest,
+    responses(
+        (status = 204, description = "action applied"),
+        (status = 403, description = "action names a different player"),
+        (status = 404, description = "game or player not found"),
+        (status = 409, description = "action rejected; body is the error code"),
+    )
+)]
+#[tracing::instrument(skip_all, fields(game_id = %game_id))]
+async fn post_action(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Json(payload): Json<ActionRequest>,
+) -> impl IntoResponse {
+    match process_action(&state, &game_id, &payload.player_id, payload.action).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => (err.status(), err.code()).into_response(),
+    }
+}
+
+/// Tracks one spectator and announces the new count when it joins and leaves.
+struct SpectatorGuard {
+    presence: Arc<Presence>,
+    game_id: String,
+    tx: broadcast::Sender<ServerMessage>,
+}
+
+impl SpectatorGuard {
+    fn new(state: &AppState, game_id: &str, tx: broadcast::Sender<ServerMessage>) -> Self {
+        let count = state.presence.add_spectator(game_id);
+        let _ = tx.send(ServerMessage::Spectators { count });
+        Self {
+            presence: state.presence.clone(),
+            game_id: game_id.to_string(),
+            tx,
+        }
+    }
+}
+
+impl Drop for SpectatorGuard {
+    fn drop(&mut self) {
+        let count = self.presence.remove_spectator(&self.game_id);
+        let _ = self.tx.send(ServerMessage::Spectators { count });
+    }
+}
+
+/// Marks a player online while held and announces online/offline transitions.
+struct PlayerGuard {
+    presence: Arc<Presence>,
+    game_id: String,
+    player_id: String,
+    tx: broadcast::Sender<ServerMessage>,
+}
+
+impl PlayerGuard {
+    fn new(
+        state: &AppState,
+        game_id: &str,
+        player_id: &str,
+        tx: broadcast::Sender<ServerMessage>,
+    ) -> Self {
+        if state.presence.player_connected(game_id, player_id) {
+            let _ = tx.send(ServerMessage::PresenceChanged {
+                player_id: player_id.to_string(),
+                online: true,
+            });
+        }
+        Self {
+            presence: state.presence.clone(),
+            game_id: game_id.to_string(),
+            player_id: player_id.to_string(),
+            tx,
+        }
+    }
+}
+
+impl Drop for PlayerGuard {
+    fn drop(&mut self) {
+        if self.presence.player_disconnected(&self.game_id, &self.player_id) {
+            let _ = self.tx.send(ServerMessage::PresenceChanged {
+                player_id: self.player_id.clone(),
+                online: false,
+            });
+        }
+    }
+}
+
+/// Counts a live socket for as long as it is held.
+struct ConnectionGuard(prometheus::IntGauge);
 
-    let _ = (&mut send_task).await;
-    recv_task.abort();
+impl ConnectionGuard {
+    fn new(gauge: prometheus::IntGauge) -> Self {
+        gauge.inc();
+        Self(gauge)
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
 }
 
+/// Apply a player's action, then let any bots whose turn it becomes move.
 async fn process_action(
     state: &AppState,
     game_id: &str,
     player_id: &str,
     action: PlayerAction,
+) -> Result<(), GameActionError> {
+    apply_logged(state, game_id, player_id, action).await?;
+    spawn_bots(state, game_id);
+    Ok(())
+}
+
+/// Run `play_bots` in the background, so the request that handed a bot the
+/// turn returns straight away.
+fn spawn_bots(state: &AppState, game_id: &str) {
+    let (state, game_id) = (state.clone(), game_id.to_string());
+    tokio::spawn(async move { play_bots(&state, &game_id).await }.in_current_span());
+}
+
+/// Move for bots until a human is up or the game is over, pausing before
+/// each move so spectators see them land one at a time. Bot moves are
+/// logged, counted and broadcast exactly like everyone else's.
+async fn play_bots(state: &AppState, game_id: &str) {
+    let delay = Duration::from_millis(state.config.bot_move_delay_ms);
+    while let Some((bot_id, action)) = next_bot_move(state, game_id, false).await {
+        tokio::time::sleep(delay).await;
+        if apply_logged(state, game_id, &bot_id, action).await.is_ok() {
+            continue;
+        }
+        // Already logged. Rather than leave the table waiting on the bot,
+        // fall back to the first legal move; only give up if that fails
+        // too, since retrying would spin.
+        let Some((bot_id, action)) = next_bot_move(state, game_id, true).await else {
+            break;
+        };
+        if apply_logged(state, game_id, &bot_id, action).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// The active bot's move, or with `first_legal` (or when its strategy has
+/// nothing) the first legal move, as `AlwaysOpen` would play.
+async fn next_bot_move(
+    state: &AppState,
+    game_id: &str,
+    first_legal: bool,
+) -> Option<(String, PlayerAction)> {
+    let games = state.games.read().await;
+    let game = games.get(game_id)?;
+    let active = game.active_player.clone()?;
+    let strategy = game.players.iter().find(|p| p.id == active)?.bot?;
+    let core = to_core(game.clone());
+    let chosen = if first_legal {
+        None
+    } else {
+        strategy.build(rand::thread_rng()).choose(&core, &active)
+    };
+    let action = chosen.or_else(|| game_core::legal_actions(&core, &active).into_iter().next())?;
+    Some((active, action))
+}
+
+#[tracing::instrument(skip_all, fields(game_id = %game_id, player_id = %player_id, action = action.kind()))]
+async fn apply_logged(
+    state: &AppState,
+    game_id: &str,
+    player_id: &str,
+    action: PlayerAction,
+) -> Result<(), GameActionError> {
+    let kind = action.kind();
+    let result = apply_and_broadcast(state, game_id, player_id, action).await;
+    match &result {
+        Ok(()) => {
+            state
+                .metrics
+                .actions_applied
+                .with_label_values(&[kind])
+                .inc();
+            info!("action applied");
+        }
+        Err(err) => {
+            state
+                .metrics
+                .actions_rejected
+                .with_label_values(&[err.code()])
+                .inc();
+            warn!(reason = err.code(), "action rejected");
+        }
+    }
+    result
+}
+
+async fn apply_and_broadcast(
+    state: &AppState,
+    game_id: &str,
+    player_id: &str,
+    action: PlayerAction,
 ) -> Result<(), GameActionError> {
     let mut games = state.games.write().await;
     let game_record = games
@@ -537,16 +1811,32 @@ async fn process_action(
         return Err(GameActionError::PlayerNotFound);
     }
 
+    // Player ids are public, so a sender may only move for themselves.
+    if action.player_id() != player_id {
+        return Err(GameActionError::NotYourAction);
+    }
+
     let mut core_game = to_core(game_record.clone());
     let events = game_core::apply_action(&mut core_game, action)?;
     // update record from core
     update_record_from_core(game_record, core_game);
 
+    let steals = events
+        .iter()
+        .filter(|evt| matches!(evt, GameEvent::GiftStolen { .. }))
+        .count();
+    state.metrics.steals.inc_by(steals as u64);
+
     // broadcast state + events
     if let Some(tx) = state.channels.read().await.get(game_id) {
-        let _ = tx.send(ServerMessage::State(to_view(game_record)));
+        let _ = tx.send(ServerMessage::State(state.view(game_record)));
         for evt in events {
-            let _ = tx.send(ServerMessage::Event(evt));
+            let finished = matches!(evt, GameEvent::GameFinished);
+            let _ = tx.send(ServerMessage::Event { event: evt });
+            if finished {
+                let stats = GameStats::from_history(&game_record.history);
+                let _ = tx.send(ServerMessage::Stats(stats));
+            }
         }
     }
     drop(games);
@@ -563,10 +1853,43 @@ enum GameActionError {
     PlayerNotFound,
     #[error("wrong phase")]
     WrongPhase,
+    #[error("spectators cannot act")]
+    ReadOnly,
+    #[error("action names a different player")]
+    NotYourAction,
+    #[error("binary frames are not supported")]
+    UnsupportedFrame,
     #[error("core error: {0}")]
     Core(#[from] game_core::GameError),
 }
 
+impl GameActionError {
+    fn code(&self) -> &'static str {
+        match self {
+            GameActionError::GameNotFound => "game_not_found",
+            GameActionError::PlayerNotFound => "player_not_found",
+            GameActionError::WrongPhase => "wrong_phase",
+            GameActionError::ReadOnly => "read_only",
+            GameActionError::NotYourAction => "not_your_action",
+            GameActionError::UnsupportedFrame => "unsupported_frame",
+            GameActionError::Core(err) => err.code(),
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            GameActionError::GameNotFound | GameActionError::PlayerNotFound => {
+                StatusCode::NOT_FOUND
+            }
+            GameActionError::ReadOnly | GameActionError::NotYourAction => {
+                StatusCode::FORBIDDEN
+            }
+            GameActionError::UnsupportedFrame => StatusCode::BAD_REQUEST,
+            GameActionError::WrongPhase | GameActionError::Core(_) => StatusCode::CONFLICT,
+        }
+    }
+}
+
 fn to_core(record: GameRecord) -> Game {
     Game {
         id: record.id,
@@ -600,6 +1923,7 @@ fn to_core(record: GameRecord) -> Game {
         current_turn: record.current_turn,
         active_player: record.active_player.clone(),
         history: record.history.clone(),
+        rules: record.rules.clone(),
     }
 }
 
@@ -627,17 +1951,6 @@ fn update_record_from_core(record: &mut GameRecord, core: Game) {
         .collect();
 }
 
-fn to_view(game: &GameRecord) -> GameView {
-    GameView {
-        id: game.id.clone(),
-        phase: game.phase.clone(),
-        players: game.players.clone(),
-        gifts: game.gifts.clone(),
-        turn_order: game.turn_order.clone(),
-        active_player: game.active_player.clone(),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -658,60 +1971,249 @@ mod tests {
         (app(state.clone()), state)
     }
 
-    #[tokio::test]
-    async fn create_game_returns_ids() {
-        let (app, _) = test_app();
-        let res = app
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method(Method::POST)
-                    .uri("/game")
-                    .header("x-admin-password", "changeme")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
-
-        assert_eq!(res.status(), StatusCode::CREATED);
-        let body = json_body(res).await;
-        assert!(body.get("game_id").and_then(|v| v.as_str()).is_some());
-        assert!(body.get("host_token").and_then(|v| v.as_str()).is_some());
+    /// Bots move in the background; wait on the game's broadcasts until
+    /// `done` holds for its record.
+    async fn wait_until(state: &AppState, game_id: &str, done: impl Fn(&GameRecord) -> bool) {
+        let mut rx = state.channel(game_id).await.subscribe();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !done(&state.games.read().await[game_id]) {
+                // Lagging is fine, the record is checked again either way.
+                let _ = rx.recv().await;
+            }
+        })
+        .await
+        .expect("game never settled");
     }
 
-    #[tokio::test]
-    async fn create_game_requires_admin_password() {
-        let (app, _) = test_app();
-        let res = app
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method(Method::POST)
-                    .uri("/game")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
-            .await
-            .unwrap();
-        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    struct StartedGame {
+        game_id: String,
+        host_token: String,
+        spectator_token: String,
+        turn_order: Vec<String>,
     }
 
-    #[tokio::test]
-    async fn join_success_and_duplicate_name_rejected() {
-        let (app, _state) = test_app();
-        // create game
-        let res = app
-            .clone()
-            .oneshot(
-                Request::builder()
-                    .method(Method::POST)
-                    .uri("/game")
-                    .header("x-admin-password", "changeme")
-                    .body(Body::empty())
+    /// Create a game, join `names`, submit a gift for each and start with a
+    /// fixed seed.
+    async fn started_game(app: &Router, names: &[&str]) -> StartedGame {
+        let created = json_body(
+            app.clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri("/game")
+                        .header("x-admin-password", "changeme")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap(),
+        )
+        .await;
+        let game_id = created["game_id"].as_str().unwrap().to_string();
+        let host_token = created["host_token"].as_str().unwrap().to_string();
+        let spectator_token = created["spectator_token"].as_str().unwrap().to_string();
+
+        for name in names {
+            let joined = json_body(
+                app.clone()
+                    .oneshot(
+                        Request::builder()
+                            .method(Method::POST)
+                            .uri(format!("/game/{game_id}/join"))
+                            .header("content-type", "application/json")
+                            .body(Body::from(json!({ "name": name }).to_string()))
+                            .unwrap(),
+                    )
+                    .await
                     .unwrap(),
             )
-            .await
+            .await;
+            let pid = joined["player_id"].as_str().unwrap();
+            let res = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(format!("/game/{game_id}/gift"))
+                        .header("content-type", "application/json")
+                        .body(Body::from(
+                            json!({ "player_id": pid, "product_url": format!("https://example.com/{name}"), "hint": format!("from {name}") }).to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        let started = json_body(
+            app.clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(format!("/game/{game_id}/start?seed=7"))
+                        .header("x-host-token", &host_token)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap(),
+        )
+        .await;
+        let turn_order = started["turn_order"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect();
+
+        StartedGame {
+            game_id,
+            host_token,
+            spectator_token,
+            turn_order,
+        }
+    }
+
+    /// Serve `app` on an ephemeral port for socket tests.
+    async fn serve(app: Router) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    type TestSocket =
+        tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+    /// Next text frame, failing the test if none arrives promptly.
+    async fn next_text(socket: &mut TestSocket) -> String {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+        loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(2), socket.next())
+                .await
+                .expect("socket message")
+                .expect("socket open")
+                .unwrap();
+            if let WsMessage::Text(text) = msg {
+                return text;
+            }
+        }
+    }
+
+    /// Send a hello for the current protocol asking for `features`.
+    async fn send_hello(socket: &mut TestSocket, features: &[&str]) {
+        let hello = json!({
+            "type": "hello",
+            "protocol_version": PROTOCOL_VERSION,
+            "features": features,
+        });
+        socket
+            .send(tokio_tungstenite::tungstenite::Message::Text(hello.to_string()))
+            .await
+            .unwrap();
+    }
+
+    /// Open a socket and complete the hello/welcome handshake.
+    async fn connect(url: String) -> TestSocket {
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        send_hello(&mut socket, &[]).await;
+        let welcome: serde_json::Value =
+            serde_json::from_str(&next_text(&mut socket).await).unwrap();
+        assert_eq!(welcome["type"], "welcome");
+        socket
+    }
+
+    /// Next binary frame decoded with `encoding`, failing on text frames.
+    async fn next_decoded(socket: &mut TestSocket, encoding: Encoding) -> ServerMessage {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+        loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(2), socket.next())
+                .await
+                .expect("socket message")
+                .expect("socket open")
+                .unwrap();
+            match msg {
+                WsMessage::Binary(bytes) => return encoding.decode(&bytes).unwrap(),
+                WsMessage::Text(text) => panic!("unexpected text frame {text}"),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skip frames until one satisfies `pred`.
+    async fn next_matching(socket: &mut TestSocket, pred: impl Fn(&str) -> bool) -> String {
+        loop {
+            let text = next_text(socket).await;
+            if pred(&text) {
+                return text;
+            }
+        }
+    }
+
+    /// Gift ids in submission order.
+    async fn gift_ids(state: &AppState, game_id: &str) -> Vec<String> {
+        state.games.read().await[game_id]
+            .gifts
+            .iter()
+            .map(|g| g.id.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn create_game_returns_ids() {
+        let (app, _) = test_app();
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/game")
+                    .header("x-admin-password", "changeme")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body = json_body(res).await;
+        assert!(body.get("game_id").and_then(|v| v.as_str()).is_some());
+        assert!(body.get("host_token").and_then(|v| v.as_str()).is_some());
+    }
+
+    #[tokio::test]
+    async fn create_game_requires_admin_password() {
+        let (app, _) = test_app();
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/game")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn join_success_and_duplicate_name_rejected() {
+        let (app, _state) = test_app();
+        // create game
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/game")
+                    .header("x-admin-password", "changeme")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
             .unwrap();
         let created = json_body(res).await;
         let game_id = created["game_id"].as_str().unwrap();
@@ -1066,10 +2568,125 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn persistence_writes_and_loads_games() {
+    async fn configured_limits_cap_games_and_players() {
+        let mut config = Config::default();
+        config.limits.max_games = 1;
+        config.limits.max_players = 1;
+        let app = app(AppState::from_config(config).await);
+
+        let create = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/game")
+                .header("x-admin-password", "changeme")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let res = app.clone().oneshot(create()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let game_id = json_body(res).await["game_id"].as_str().unwrap().to_string();
+
+        let res = app.clone().oneshot(create()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let join = |name: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/game/{game_id}/join"))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "name": name }).to_string()))
+                .unwrap()
+        };
+        let res = app.clone().oneshot(join("alice")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app.clone().oneshot(join("bob")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn serves_static_frontend_with_spa_fallback() {
+        let dir = std::env::temp_dir().join(format!("ce_static_{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(dir.join("assets")).await.unwrap();
+        let index = format!("<!doctype html><title>elephant</title>{}", " ".repeat(256));
+        tokio::fs::write(dir.join("index.html"), &index).await.unwrap();
+        tokio::fs::write(dir.join("assets/app-1234.js"), "console.log(1)")
+            .await
+            .unwrap();
+
+        let config = Config {
+            static_dir: Some(dir.clone()),
+            ..Config::default()
+        };
+        let app = app(AppState::from_config(config).await);
+        let get = |uri: &str| {
+            Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .header("accept-encoding", "gzip")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let res = app.clone().oneshot(get("/")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["cache-control"], "no-cache");
+        assert_eq!(res.headers()["content-encoding"], "gzip");
+
+        // A revalidation 304 has no content type but must stay no-cache, or
+        // the browser would keep index.html for an hour.
+        let last_modified = res.headers()["last-modified"].clone();
+        let mut revalidate = get("/");
+        revalidate
+            .headers_mut()
+            .insert("if-modified-since", last_modified);
+        let res = app.clone().oneshot(revalidate).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers()["cache-control"], "no-cache");
+
+        // client-side route falls back to index.html
+        let res = app.clone().oneshot(get("/lobby/abc")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+
+        let res = app.clone().oneshot(get("/assets/app-1234.js")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers()["cache-control"]
+            .to_str()
+            .unwrap()
+            .contains("immutable"));
+
+        let res = app.clone().oneshot(get("/assets/missing.js")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        // API routes still win over the fallback
+        let res = app.clone().oneshot(get("/game/unknown")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            res.into_body().collect().await.unwrap().to_bytes(),
+            "game not found"
+        );
+
+        let _ = tokio::fs::remove_dir_all(dir).await;
+    }
+
+    #[tokio::test]
+    async fn healthz_and_readyz_report_status() {
         let path = std::env::temp_dir().join(format!("ce_state_{}.json", Uuid::new_v4()));
         let state = AppState::with_persistence(path.clone()).await;
-        let app = app(state.clone());
+        let app = app(state);
+        let get = |uri: &str| {
+            Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let res = app.clone().oneshot(get("/healthz")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
 
         let res = app
             .clone()
@@ -1085,13 +2702,1042 @@ mod tests {
             .unwrap();
         assert_eq!(res.status(), StatusCode::CREATED);
 
-        // ensure file exists
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        assert!(tokio::fs::metadata(&path).await.is_ok());
+        let res = app.clone().oneshot(get("/readyz")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = json_body(res).await;
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["persistence"], "ok");
+        assert_eq!(body["games"], 1);
+        assert_eq!(body["connected_sockets"], 0);
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        let _ = tokio::fs::remove_file(path).await;
+
+        // persistence pointing into a missing directory is not ready
+        let missing = std::env::temp_dir()
+            .join(format!("ce_missing_{}", Uuid::new_v4()))
+            .join("state.json");
+        let app = super::app(AppState::with_persistence(missing).await);
+        let res = app.clone().oneshot(get("/readyz")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = json_body(res).await;
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(body["persistence"], "unwritable");
+    }
 
-        // load new state from disk
-        let loaded = AppState::with_persistence(path.clone()).await;
-        let games = loaded.games.read().await;
-        assert_eq!(games.len(), 1);
+    #[tokio::test]
+    async fn metrics_count_handlers_and_actions() {
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let gifts = gift_ids(&state, &game.game_id).await;
+        let first = &game.turn_order[0];
+        let second = &game.turn_order[1];
+
+        // out of turn
+        let err = process_action(
+            &state,
+            &game.game_id,
+            second,
+            PlayerAction::ChooseGift {
+                player_id: second.clone(),
+                gift_id: gifts[0].clone(),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code(), "not_players_turn");
+
+        process_action(
+            &state,
+            &game.game_id,
+            first,
+            PlayerAction::ChooseGift {
+                player_id: first.clone(),
+                gift_id: gifts[0].clone(),
+            },
+        )
+        .await
+        .unwrap();
+        process_action(
+            &state,
+            &game.game_id,
+            second,
+            PlayerAction::StealGift {
+                player_id: second.clone(),
+                gift_id: gifts[0].clone(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = res.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        for line in [
+            "elephant_games_created_total 1",
+            "elephant_players_joined_total 2",
+            "elephant_gifts_submitted_total 2",
+            "elephant_actions_applied_total{action=\"choose_gift\"} 1",
+            "elephant_actions_applied_total{action=\"steal_gift\"} 1",
+            "elephant_actions_rejected_total{reason=\"not_players_turn\"} 1",
+            "elephant_steals_total 1",
+            "elephant_ws_connections 0",
+        ] {
+            assert!(text.contains(line), "missing `{line}` in:\n{text}");
+        }
+    }
+
+    /// Two-player game: first opens a gift, second steals it, first opens the
+    /// other gift and the game finishes.
+    async fn finished_game(app: &Router, state: &AppState) -> StartedGame {
+        let game = started_game(app, &["alice", "bob"]).await;
+        let gifts = gift_ids(state, &game.game_id).await;
+        let (first, second) = (&game.turn_order[0], &game.turn_order[1]);
+        for (actor, action) in [
+            (
+                first,
+                PlayerAction::ChooseGift {
+                    player_id: first.clone(),
+                    gift_id: gifts[0].clone(),
+                },
+            ),
+            (
+                second,
+                PlayerAction::StealGift {
+                    player_id: second.clone(),
+                    gift_id: gifts[0].clone(),
+                },
+            ),
+            (
+                first,
+                PlayerAction::ChooseGift {
+                    player_id: first.clone(),
+                    gift_id: gifts[1].clone(),
+                },
+            ),
+        ] {
+            process_action(state, &game.game_id, actor, action)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            state.games.read().await[&game.game_id].phase,
+            GamePhase::Finished
+        ));
+        game
+    }
+
+    #[tokio::test]
+    async fn results_export_requires_host_and_omits_token() {
+        let (app, state) = test_app();
+        let game = finished_game(&app, &state).await;
+        let results = |query: &str, token: Option<&str>| {
+            let mut req = Request::builder()
+                .method(Method::GET)
+                .uri(format!("/game/{}/results{query}", game.game_id));
+            if let Some(token) = token {
+                req = req.header("x-host-token", token);
+            }
+            req.body(Body::empty()).unwrap()
+        };
+
+        let res = app.clone().oneshot(results("", None)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = app
+            .clone()
+            .oneshot(results("", Some(&game.host_token)))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = json_body(res).await;
+        assert!(!body.to_string().contains(&game.host_token));
+        let rows = body["results"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        let second = &game.turn_order[1];
+        let stolen = rows.iter().find(|r| &r["player_id"] == second).unwrap();
+        assert_eq!(stolen["times_stolen"], 1);
+        assert!(stolen["product_url"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://example.com/"));
+
+        let res = app
+            .clone()
+            .oneshot(results("?format=csv", Some(&game.host_token)))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv"));
+        let bytes = res.into_body().collect().await.unwrap().to_bytes();
+        let csv = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(csv.starts_with("player,gift,submitted_by,product_url,times_stolen\n"));
+        assert_eq!(csv.lines().count(), 3);
+
+        let res = app
+            .clone()
+            .oneshot(results("?format=md", Some(&game.host_token)))
+            .await
+            .unwrap();
+        let bytes = res.into_body().collect().await.unwrap().to_bytes();
+        let md = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(md.starts_with("| player | gift |"));
+        assert!(!md.contains(&game.host_token));
+    }
+
+    #[tokio::test]
+    async fn results_unavailable_before_finish() {
+        let (app, _) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/game/{}/results", game.game_id))
+                    .header("x-host-token", &game.host_token)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn stats_served_and_broadcast_after_finish() {
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let mut rx = state.channels.read().await[&game.game_id].subscribe();
+        let stats_req = || {
+            Request::builder()
+                .method(Method::GET)
+                .uri(format!("/game/{}/stats", game.game_id))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let res = app.clone().oneshot(stats_req()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let gifts = gift_ids(&state, &game.game_id).await;
+        for (actor, gift) in game.turn_order.iter().zip(&gifts) {
+            process_action(
+                &state,
+                &game.game_id,
+                actor,
+                PlayerAction::ChooseGift {
+                    player_id: actor.clone(),
+                    gift_id: gift.clone(),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let res = app.clone().oneshot(stats_req()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = json_body(res).await;
+        assert_eq!(body["total_steals"], 0);
+        assert_eq!(body["kept_by_opener"].as_array().unwrap().len(), 2);
+
+        let mut last_two = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            last_two.push(msg);
+            if last_two.len() > 2 {
+                last_two.remove(0);
+            }
+        }
+        assert!(matches!(
+            last_two[0],
+            ServerMessage::Event {
+                event: GameEvent::GameFinished
+            }
+        ));
+        assert!(matches!(last_two[1], ServerMessage::Stats(_)));
+    }
+
+    #[tokio::test]
+    async fn timeline_replays_finished_game() {
+        let (app, state) = test_app();
+        let game = finished_game(&app, &state).await;
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/game/{}/timeline", game.game_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = json_body(res).await;
+        assert_eq!(body["initial_active_player"], game.turn_order[0].as_str());
+        let steps = body["steps"].as_array().unwrap();
+        let history_len = state.games.read().await[&game.game_id].history.len();
+        assert_eq!(steps.len(), history_len);
+        assert_eq!(steps[0]["event"]["type"], "gift_opened");
+        assert_eq!(steps.last().unwrap()["event"]["type"], "game_finished");
+        assert_eq!(steps.last().unwrap()["holdings"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn spectator_socket_is_redacted_read_only_and_counted() {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let (app, _) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let addr = serve(app).await;
+        let url = |token: &str| format!("ws://{addr}/ws/{}/spectate?token={token}", game.game_id);
+
+        match tokio_tungstenite::connect_async(url("wrong")).await {
+            Err(tokio_tungstenite::tungstenite::Error::Http(res)) => {
+                assert_eq!(res.status(), StatusCode::UNAUTHORIZED)
+            }
+            other => panic!("expected 401, got {other:?}"),
+        }
+
+        let mut projector = connect(url(&game.spectator_token)).await;
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&next_text(&mut projector).await).unwrap();
+        assert_eq!(snapshot["type"], "state");
+        assert_eq!(snapshot["spectators"], 1);
+        for gift in snapshot["gifts"].as_array().unwrap() {
+            assert_eq!(gift["product_url"], "");
+            assert_eq!(gift["submitted_by"], "");
+            assert!(!gift["hint"].as_str().unwrap().is_empty());
+        }
+
+        let action = json!({
+            "type": "action",
+            "choose_gift": { "player_id": game.turn_order[0], "gift_id": "x" }
+        });
+        projector
+            .send(WsMessage::Text(action.to_string()))
+            .await
+            .unwrap();
+        next_matching(&mut projector, |t| t == "error:ReadOnly").await;
+
+        // the host token also works, and everyone sees the new count
+        let remote = connect(url(&game.host_token)).await;
+        next_matching(&mut projector, |t| t.contains(r#""count":2"#)).await;
+        drop(remote);
+        next_matching(&mut projector, |t| t.contains(r#""count":1"#)).await;
+    }
+
+    #[tokio::test]
+    async fn presence_tracks_player_sockets() {
+        let (app, _) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let addr = serve(app.clone()).await;
+        let url = |pid: &str| format!("ws://{addr}/ws/{}/{pid}", game.game_id);
+        let (first, second) = (&game.turn_order[0], &game.turn_order[1]);
+
+        let mut host_screen = connect(url(first)).await;
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&next_text(&mut host_screen).await).unwrap();
+        let online = |view: &serde_json::Value, pid: &str| {
+            view["players"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|p| p["id"] == pid)
+                .unwrap()["online"]
+                .as_bool()
+                .unwrap()
+        };
+        assert!(online(&snapshot, first));
+        assert!(!online(&snapshot, second));
+
+        let other = connect(url(second)).await;
+        let joined = next_matching(&mut host_screen, |t| {
+            t.contains("presence_changed") && t.contains(second.as_str())
+        })
+        .await;
+        let joined: serde_json::Value = serde_json::from_str(&joined).unwrap();
+        assert_eq!(joined["player_id"], second.as_str());
+        assert_eq!(joined["online"], true);
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::GET)
+                    .uri(format!("/game/{}", game.game_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(online(&json_body(res).await, second));
+
+        drop(other);
+        let left = next_matching(&mut host_screen, |t| {
+            t.contains("presence_changed") && t.contains(second.as_str())
+        })
+        .await;
+        let left: serde_json::Value = serde_json::from_str(&left).unwrap();
+        assert_eq!(left["player_id"], second.as_str());
+        assert_eq!(left["online"], false);
+    }
+
+    #[tokio::test]
+    async fn heartbeat_drops_silent_sockets_and_keeps_live_ones() {
+        let state = AppState::new(Config {
+            ws_ping_interval_ms: 50,
+            ws_max_missed_pongs: 1,
+            ..Config::default()
+        });
+        let app = app(state.clone());
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let addr = serve(app).await;
+        let url = |pid: &str| format!("ws://{addr}/ws/{}/{pid}", game.game_id);
+        let (first, second) = (&game.turn_order[0], &game.turn_order[1]);
+
+        // never reads, so never answers a ping
+        let _silent = connect(url(first)).await;
+        // keeps reading, which makes tungstenite answer pings
+        let mut live = connect(url(second)).await;
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(500);
+        while tokio::time::Instant::now() < deadline {
+            let _ = tokio::time::timeout_at(deadline, live.next()).await;
+        }
+
+        let online = state.presence.online_players(&game.game_id);
+        assert!(!online.contains(first.as_str()));
+        assert!(online.contains(second.as_str()));
+    }
+
+    #[tokio::test]
+    async fn binary_frames_are_rejected_and_close_ends_socket() {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let addr = serve(app).await;
+        let pid = &game.turn_order[0];
+        let mut socket = connect(format!("ws://{addr}/ws/{}/{pid}", game.game_id)).await;
+        next_text(&mut socket).await;
+
+        socket.send(WsMessage::Binary(vec![1, 2, 3])).await.unwrap();
+        next_matching(&mut socket, |t| t == "error:UnsupportedFrame").await;
+        assert!(state.presence.online_players(&game.game_id).contains(pid.as_str()));
+
+        socket.send(WsMessage::Close(None)).await.unwrap();
+        let closed = tokio::time::timeout(std::time::Duration::from_secs(2), async {
+            while state.presence.online_players(&game.game_id).contains(pid.as_str()) {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(closed.is_ok(), "socket should be torn down after close");
+    }
+
+    #[tokio::test]
+    async fn sse_stream_and_rest_actions_mirror_the_socket() {
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let (first, second) = (&game.turn_order[0], &game.turn_order[1]);
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/game/{}/events?player_id={first}", game.game_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "text/event-stream");
+        assert!(state.presence.online_players(&game.game_id).contains(first.as_str()));
+        let mut body = res.into_body();
+
+        // Collect `data:` payloads until one matches.
+        async fn next_data(
+            body: &mut Body,
+            pred: impl Fn(&serde_json::Value) -> bool,
+        ) -> serde_json::Value {
+            loop {
+                let frame = tokio::time::timeout(std::time::Duration::from_secs(2), body.frame())
+                    .await
+                    .expect("sse frame")
+                    .expect("stream open")
+                    .unwrap();
+                let Ok(chunk) = frame.into_data() else { continue };
+                for line in std::str::from_utf8(&chunk).unwrap().lines() {
+                    if let Some(data) = line.strip_prefix("data: ") {
+                        let msg: serde_json::Value = serde_json::from_str(data).unwrap();
+                        if pred(&msg) {
+                            return msg;
+                        }
+                    }
+                }
+            }
+        }
+
+        let snapshot = next_data(&mut body, |m| m["type"] == "state").await;
+        assert_eq!(snapshot["active_player"], first.as_str());
+
+        let gift_id = gift_ids(&state, &game.game_id).await[0].clone();
+        let act = |player_id: &str, action: serde_json::Value| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/game/{}/action", game.game_id))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "player_id": player_id, "action": action }).to_string(),
+                ))
+                .unwrap()
+        };
+
+        let res = app
+            .clone()
+            .oneshot(act(
+                second,
+                json!({ "choose_gift": { "player_id": second, "gift_id": gift_id } }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let res = app
+            .clone()
+            .oneshot(act(
+                first,
+                json!({ "choose_gift": { "player_id": first, "gift_id": gift_id } }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+        let opened = next_data(&mut body, |m| m["type"] == "event").await;
+        assert_eq!(opened["event"]["type"], "gift_opened");
+        assert_eq!(opened["event"]["player_id"], first.as_str());
+
+        drop(body);
+        assert!(state.presence.online_players(&game.game_id).is_empty());
+    }
+
+    #[tokio::test]
+    async fn actions_cannot_be_made_for_another_player() {
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let (first, second) = (&game.turn_order[0], &game.turn_order[1]);
+        let gift_id = gift_ids(&state, &game.game_id).await[0].clone();
+
+        // `second` claims to be the active player.
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/game/{}/action", game.game_id))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({
+                            "player_id": second,
+                            "action": { "choose_gift": { "player_id": first, "gift_id": gift_id } },
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"not_your_action");
+
+        let games = state.games.read().await;
+        let record = &games[&game.game_id];
+        assert!(record.history.is_empty());
+        assert_eq!(record.active_player.as_ref(), Some(first));
+    }
+
+    #[tokio::test]
+    async fn delta_mode_sends_sequenced_patches() {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let addr = serve(app.clone()).await;
+        let pid = &game.turn_order[0];
+        let mut socket = connect(format!(
+            "ws://{addr}/ws/{}/{pid}?delta=true",
+            game.game_id
+        ))
+        .await;
+
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&next_text(&mut socket).await).unwrap();
+        assert_eq!(snapshot["type"], "snapshot");
+        assert_eq!(snapshot["seq"], 1);
+        let mut view = snapshot["state"].clone();
+
+        let gift_id = gift_ids(&state, &game.game_id).await[0].clone();
+        let action = json!({
+            "type": "action",
+            "choose_gift": { "player_id": pid, "gift_id": gift_id }
+        });
+        socket
+            .send(WsMessage::Text(action.to_string()))
+            .await
+            .unwrap();
+
+        let patch = next_matching(&mut socket, |t| t.contains("\"patch\"")).await;
+        let patch: serde_json::Value = serde_json::from_str(&patch).unwrap();
+        assert_eq!(patch["base"], 1);
+        assert_eq!(patch["seq"], 2);
+        let ops: Vec<PatchOp> = serde_json::from_value(patch["ops"].clone()).unwrap();
+        assert!(!ops.is_empty());
+        delta::apply(&mut view, &ops);
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/game/{}", game.game_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(view, json_body(res).await);
+
+        socket
+            .send(WsMessage::Text(json!({ "type": "resync" }).to_string()))
+            .await
+            .unwrap();
+        let resynced = next_matching(&mut socket, |t| t.contains("\"snapshot\"")).await;
+        let resynced: serde_json::Value = serde_json::from_str(&resynced).unwrap();
+        assert_eq!(resynced["seq"], 3);
+        assert_eq!(resynced["state"], view);
+    }
+
+    #[tokio::test]
+    async fn state_payload_drives_the_shared_rules() {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let state = AppState::new(Config {
+            rules: Rules {
+                max_steals_per_gift: 1,
+            },
+            ..Config::default()
+        });
+        let app = app(state.clone());
+        let game = started_game(&app, &["alice", "bob", "carol"]).await;
+        let addr = serve(app.clone()).await;
+        let [first, second, third] = &game.turn_order[..] else {
+            unreachable!()
+        };
+        let mut socket = connect(format!("ws://{addr}/ws/{}/{first}", game.game_id)).await;
+
+        // What the browser hands to game-wasm: the `state` message as sent.
+        async fn next_game(socket: &mut TestSocket) -> Game {
+            let text = next_matching(socket, |t| t.contains(r#""type":"state""#)).await;
+            serde_json::from_str(&text).expect("state payload is a game-core Game")
+        }
+
+        let served = next_game(&mut socket).await;
+        assert_eq!(served.rules.max_steals_per_gift, 1);
+        let open = game_core::legal_actions(&served, first)[0].clone();
+        let frame = serde_json::to_string(&ClientMessage::Action(open)).unwrap();
+        socket.send(WsMessage::Text(frame)).await.unwrap();
+        let served = next_game(&mut socket).await;
+
+        let steal = game_core::legal_actions(&served, second)
+            .into_iter()
+            .find(|a| a.kind() == "steal_gift")
+            .expect("second player can steal");
+        let mut preview = served.clone();
+        game_core::apply_action(&mut preview, steal.clone()).unwrap();
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/game/{}/action", game.game_id))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({ "player_id": second, "action": steal }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        let served = next_game(&mut socket).await;
+        assert_eq!(served.gifts, preview.gifts);
+        assert_eq!(served.active_player, preview.active_player);
+        assert_eq!(served.history, preview.history);
+
+        // The game's own steal limit applies, not the default of three.
+        let PlayerAction::StealGift { gift_id, .. } = steal else {
+            unreachable!()
+        };
+        let mut later = served;
+        later.active_player = Some(third.clone());
+        assert_eq!(
+            game_core::validate_action(
+                &later,
+                &PlayerAction::StealGift {
+                    player_id: third.clone(),
+                    gift_id,
+                }
+            ),
+            Err(game_core::GameError::StealLimitReached)
+        );
+    }
+
+    #[tokio::test]
+    async fn binary_encodings_negotiated_by_query_or_subprotocol() {
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob", "carol"]).await;
+        let addr = serve(app).await;
+        let gifts = gift_ids(&state, &game.game_id).await;
+
+        let cases = [
+            ("?encoding=msgpack", None, Encoding::Msgpack),
+            ("", Some("elephant.cbor"), Encoding::Cbor),
+        ];
+        for (turn, (query, subprotocol, encoding)) in cases.into_iter().enumerate() {
+            let pid = &game.turn_order[turn];
+            let mut request = format!("ws://{addr}/ws/{}/{pid}{query}", game.game_id)
+                .into_client_request()
+                .unwrap();
+            if let Some(name) = subprotocol {
+                request
+                    .headers_mut()
+                    .insert("sec-websocket-protocol", name.parse().unwrap());
+            }
+            let (mut socket, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+            if let Some(name) = subprotocol {
+                assert_eq!(response.headers()["sec-websocket-protocol"], name);
+            }
+            // text frames are JSON whatever the negotiated encoding
+            send_hello(&mut socket, &[]).await;
+            let welcome = next_decoded(&mut socket, encoding).await;
+            assert!(matches!(welcome, ServerMessage::Welcome { .. }));
+
+            let first = next_decoded(&mut socket, encoding).await;
+            assert!(matches!(first, ServerMessage::State(_)));
+
+            let action = ClientMessage::Action(PlayerAction::ChooseGift {
+                player_id: pid.clone(),
+                gift_id: gifts[turn].clone(),
+            });
+            let Message::Binary(frame) = encoding.encode(&action) else {
+                panic!("{encoding:?} should use binary frames");
+            };
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+            loop {
+                if let ServerMessage::Event {
+                    event: GameEvent::GiftOpened { player_id, gift_id },
+                } = next_decoded(&mut socket, encoding).await
+                {
+                    assert_eq!(&player_id, pid);
+                    assert_eq!(gift_id, gifts[turn]);
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fuzz_seeds_are_client_messages() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../fuzz/seeds/client_message");
+        let mut seen = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let text = std::fs::read_to_string(&path).unwrap();
+            if let Err(err) = serde_json::from_str::<ClientMessage>(&text) {
+                panic!("{} no longer parses: {err}", path.display());
+            }
+            seen += 1;
+        }
+        assert!(seen > 0, "no seeds in {dir}");
+    }
+
+    #[tokio::test]
+    async fn handshake_negotiates_features_and_refuses_other_versions() {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let (app, _) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let addr = serve(app.clone()).await;
+        let url = format!("ws://{addr}/ws/{}/{}", game.game_id, game.turn_order[0]);
+
+        let res = app
+            .clone()
+            .oneshot(Request::builder().uri("/version").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = json_body(res).await;
+        assert_eq!(body["protocol_version"], PROTOCOL_VERSION);
+        assert!(body["features"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("delta")));
+        assert!(body["encodings"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("msgpack")));
+
+        // Encodings are picked at connect time, so asking in the hello
+        // changes nothing and is not echoed back.
+        let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str()).await.unwrap();
+        send_hello(&mut socket, &["delta", "teleport", "msgpack"]).await;
+        let welcome: serde_json::Value =
+            serde_json::from_str(&next_text(&mut socket).await).unwrap();
+        assert_eq!(welcome["type"], "welcome");
+        assert_eq!(welcome["features"], json!(["delta"]));
+        let first: serde_json::Value =
+            serde_json::from_str(&next_text(&mut socket).await).unwrap();
+        assert_eq!(first["type"], "snapshot");
+
+        async fn refused(socket: &mut TestSocket) -> String {
+            let error = next_text(socket).await;
+            let close = tokio::time::timeout(std::time::Duration::from_secs(2), socket.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            let WsMessage::Close(Some(frame)) = close else {
+                panic!("expected close frame, got {close:?}");
+            };
+            assert!(!frame.reason.is_empty());
+            error
+        }
+
+        let (mut old, _) = tokio_tungstenite::connect_async(url.as_str()).await.unwrap();
+        let hello = json!({ "type": "hello", "protocol_version": PROTOCOL_VERSION + 1 });
+        old.send(WsMessage::Text(hello.to_string())).await.unwrap();
+        assert!(refused(&mut old).await.starts_with("error:IncompatibleVersion"));
+
+        let (mut rude, _) = tokio_tungstenite::connect_async(url.as_str()).await.unwrap();
+        rude.send(WsMessage::Text(json!({ "type": "resync" }).to_string()))
+            .await
+            .unwrap();
+        assert_eq!(refused(&mut rude).await, "error:MissingHello");
+    }
+
+    #[tokio::test]
+    async fn host_added_bots_take_their_own_turns() {
+        let state = AppState::new(Config {
+            bot_move_delay_ms: 0,
+            ..Config::default()
+        });
+        let app = app(state.clone());
+        let created = json_body(
+            app.clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri("/game")
+                        .header("x-admin-password", "changeme")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap(),
+        )
+        .await;
+        let game_id = created["game_id"].as_str().unwrap().to_string();
+        let host_token = created["host_token"].as_str().unwrap().to_string();
+
+        let add_bot = |name: &str, strategy: &str, token: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/game/{game_id}/bots"))
+                .header("content-type", "application/json")
+                .header("x-host-token", token)
+                .body(Body::from(
+                    json!({ "name": name, "strategy": strategy, "product_url": "https://example.com/bot", "hint": "beep" })
+                        .to_string(),
+                ))
+                .unwrap()
+        };
+        let res = app.clone().oneshot(add_bot("robo", "greedy", "nope")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        for (name, strategy) in [("robo", "greedy"), ("unit", "always_open"), ("dice", "random")] {
+            let res = app.clone().oneshot(add_bot(name, strategy, &host_token)).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            let body = json_body(res).await;
+            assert_eq!(body["gift"]["submitted_by"], body["player_id"]);
+        }
+
+        let joined = json_body(
+            app.clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(format!("/game/{game_id}/join"))
+                        .header("content-type", "application/json")
+                        .body(Body::from(json!({ "name": "alice" }).to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap(),
+        )
+        .await;
+        let alice = joined["player_id"].as_str().unwrap().to_string();
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/game/{game_id}/gift"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        json!({ "player_id": alice, "product_url": "https://example.com/a", "hint": "soft" }).to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/game/{game_id}/start?seed=7"))
+                    .header("x-host-token", &host_token)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app.clone().oneshot(add_bot("late", "greedy", &host_token)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        // Whenever control comes back, it is alice's turn or the game is over.
+        loop {
+            wait_until(&state, &game_id, |g| {
+                g.phase == GamePhase::Finished || g.active_player.as_ref() == Some(&alice)
+            })
+            .await;
+            let core = to_core(state.games.read().await[&game_id].clone());
+            if core.phase == GamePhase::Finished {
+                break;
+            }
+            assert_eq!(core.active_player.as_ref(), Some(&alice));
+            let action = game_core::legal_actions(&core, &alice).remove(0);
+            process_action(&state, &game_id, &alice, action).await.unwrap();
+        }
+
+        let game = state.games.read().await[&game_id].clone();
+        let bots: Vec<&String> = game
+            .players
+            .iter()
+            .filter(|p| p.bot.is_some())
+            .map(|p| &p.id)
+            .collect();
+        assert_eq!(bots.len(), 3);
+        for bot in bots {
+            assert!(game.history.iter().any(|e| match e {
+                GameEvent::GiftOpened { player_id, .. } => player_id == bot,
+                GameEvent::GiftStolen { to, .. } => to == bot,
+                _ => false,
+            }));
+        }
+    }
+
+    #[tokio::test]
+    async fn persistence_writes_and_loads_games() {
+        let path = std::env::temp_dir().join(format!("ce_state_{}.json", Uuid::new_v4()));
+        let state = AppState::with_persistence(path.clone()).await;
+        let app = app(state.clone());
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/game")
+                    .header("x-admin-password", "changeme")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        // ensure file exists
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(tokio::fs::metadata(&path).await.is_ok());
+
+        // load new state from disk
+        let loaded = AppState::with_persistence(path.clone()).await;
+        let games = loaded.games.read().await;
+        assert_eq!(games.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn bots_up_at_restart_resume_play() {
+        let path = std::env::temp_dir().join(format!("ce_state_{}.json", Uuid::new_v4()));
+        let state = AppState::with_persistence(path.clone()).await;
+        let app = app(state.clone());
+        let game = started_game(&app, &["alice", "bob"]).await;
+
+        // As if the server stopped while a bot was up.
+        for player in &mut state.games.write().await.get_mut(&game.game_id).unwrap().players {
+            player.bot = Some(StrategyKind::AlwaysOpen);
+        }
+        state.persist().await;
+
+        let loaded = AppState::from_config(Config {
+            persist_path: Some(path.clone()),
+            bot_move_delay_ms: 0,
+            ..Config::default()
+        })
+        .await;
+        wait_until(&loaded, &game.game_id, |g| g.phase == GamePhase::Finished).await;
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn actions_return_before_the_bots_after_them_move() {
+        let (app, state) = test_app();
+        let game = started_game(&app, &["alice", "bob"]).await;
+        let (human, bot) = (&game.turn_order[0], &game.turn_order[1]);
+        for player in &mut state.games.write().await.get_mut(&game.game_id).unwrap().players {
+            if &player.id == bot {
+                player.bot = Some(StrategyKind::AlwaysOpen);
+            }
+        }
+
+        let action = PlayerAction::ChooseGift {
+            player_id: human.clone(),
+            gift_id: state.games.read().await[&game.game_id].gifts[0].id.clone(),
+        };
+        process_action(&state, &game.game_id, human, action).await.unwrap();
+        // The bot is up but waits its turn's delay before moving.
+        let record = state.games.read().await[&game.game_id].clone();
+        assert_eq!(record.active_player.as_ref(), Some(bot));
+        assert_eq!(record.phase, GamePhase::InProgress);
+
+        wait_until(&state, &game.game_id, |g| g.phase == GamePhase::Finished).await;
     }
 }
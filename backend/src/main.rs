@@ -1,17 +1,28 @@
-use backend::{app, AppState};
-use std::env;
+use backend::config::LogFormat;
+use backend::{app, AppState, Config};
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() {
-    let state = if let Ok(path) = env::var("PERSIST_PATH") {
-        AppState::with_persistence(path).await
-    } else {
-        AppState::default()
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("config error: {err}");
+            std::process::exit(1);
+        }
     };
+    init_tracing(config.log_format);
+    if config.uses_default_password() {
+        tracing::warn!("running with the default admin password (--insecure-dev)");
+    }
+
+    let host = config.host.clone();
+    let port = config.port;
+    let state = AppState::from_config(config).await;
     let app = app(state);
-    println!("Starting server on 0.0.0.0:3000");
+    tracing::info!(%host, port, "starting server");
     axum::serve(
-        tokio::net::TcpListener::bind("0.0.0.0:3000")
+        tokio::net::TcpListener::bind((host.as_str(), port))
             .await
             .expect("bind"),
         app,
@@ -19,3 +30,12 @@ async fn main() {
     .await
     .expect("server error");
 }
+
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().with_current_span(true).init(),
+    }
+}
@@ -0,0 +1,102 @@
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+/// Prometheus collectors for one server instance. Each `AppState` owns its own
+/// registry so tests do not share counters.
+pub struct Metrics {
+    registry: Registry,
+    pub games_created: IntCounter,
+    pub players_joined: IntCounter,
+    pub gifts_submitted: IntCounter,
+    pub actions_applied: IntCounterVec,
+    pub actions_rejected: IntCounterVec,
+    pub steals: IntCounter,
+    pub ws_connections: IntGauge,
+    pub broadcast_lagged: IntCounter,
+    pub persist_seconds: Histogram,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("elephant".into()), None)
+            .expect("valid registry prefix");
+
+        let games_created =
+            IntCounter::new("games_created_total", "Games created").expect("valid metric");
+        let players_joined =
+            IntCounter::new("players_joined_total", "Players joined").expect("valid metric");
+        let gifts_submitted = IntCounter::new(
+            "gifts_submitted_total",
+            "Gift submissions, including edits",
+        )
+        .expect("valid metric");
+        let actions_applied = IntCounterVec::new(
+            Opts::new("actions_applied_total", "Player actions applied"),
+            &["action"],
+        )
+        .expect("valid metric");
+        let actions_rejected = IntCounterVec::new(
+            Opts::new("actions_rejected_total", "Player actions rejected"),
+            &["reason"],
+        )
+        .expect("valid metric");
+        let steals = IntCounter::new("steals_total", "Gifts stolen").expect("valid metric");
+        let ws_connections = IntGauge::new("ws_connections", "Open WebSocket connections")
+            .expect("valid metric");
+        let broadcast_lagged = IntCounter::new(
+            "broadcast_lagged_total",
+            "Times a socket fell behind its game's broadcast channel",
+        )
+        .expect("valid metric");
+        let persist_seconds = Histogram::with_opts(HistogramOpts::new(
+            "persist_duration_seconds",
+            "Time spent writing the persistence snapshot",
+        ))
+        .expect("valid metric");
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 9] = [
+            Box::new(games_created.clone()),
+            Box::new(players_joined.clone()),
+            Box::new(gifts_submitted.clone()),
+            Box::new(actions_applied.clone()),
+            Box::new(actions_rejected.clone()),
+            Box::new(steals.clone()),
+            Box::new(ws_connections.clone()),
+            Box::new(broadcast_lagged.clone()),
+            Box::new(persist_seconds.clone()),
+        ];
+        for collector in collectors {
+            registry.register(collector).expect("unique metric names");
+        }
+
+        Self {
+            registry,
+            games_created,
+            players_joined,
+            gifts_submitted,
+            actions_applied,
+            actions_rejected,
+            steals,
+            ws_connections,
+            broadcast_lagged,
+            persist_seconds,
+        }
+    }
+
+    /// Render all collectors in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .expect("text encoding cannot fail");
+        String::from_utf8(buf).expect("prometheus output is utf-8")
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,109 @@
+//! OpenAPI 3 description of the REST surface, generated from the handler
+//! annotations and the request/response types. Sockets are not covered.
+
+use axum::response::IntoResponse;
+use axum::Json;
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Cyber Elephant", description = "White elephant gift exchange server"),
+    paths(
+        crate::create_game,
+        crate::join_game,
+        crate::add_bot,
+        crate::submit_gift,
+        crate::start_game,
+        crate::post_action,
+        crate::events_handler,
+        crate::get_game,
+        crate::get_results,
+        crate::get_stats,
+        crate::get_timeline,
+        crate::version,
+        crate::healthz,
+        crate::readyz,
+        crate::metrics_handler,
+        openapi_json,
+    )
+)]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    responses((status = 200, description = "This document", content_type = "application/json"))
+)]
+pub async fn openapi_json() -> impl IntoResponse {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    use crate::{app, routes, AppState};
+
+    const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+    /// `(method, path)` for every route `app()` mounts, in OpenAPI form, so
+    /// a new handler cannot slip past the spec unnoticed.
+    fn registered_routes() -> BTreeSet<(String, String)> {
+        routes()
+            .into_iter()
+            // Sockets speak their own protocol, not REST.
+            .filter(|r| !r.path.starts_with("/ws/"))
+            .map(|r| {
+                let path = r
+                    .path
+                    .split('/')
+                    .map(|seg| match seg.strip_prefix(':') {
+                        Some(param) => format!("{{{param}}}"),
+                        None => seg.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                (r.method.as_str().to_lowercase(), path)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn spec_matches_registered_routes() {
+        let res = app(AppState::default())
+            .oneshot(Request::builder().uri("/openapi.json").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = res.into_body().collect().await.unwrap().to_bytes();
+        let spec: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+
+        let mut documented = BTreeSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                if METHODS.contains(&method.as_str()) {
+                    documented.insert((method.clone(), path.clone()));
+                }
+            }
+        }
+
+        let routes = registered_routes();
+        let undocumented: Vec<_> = routes.difference(&documented).collect();
+        let stale: Vec<_> = documented.difference(&routes).collect();
+        assert!(undocumented.is_empty(), "routes missing from spec: {undocumented:?}");
+        assert!(stale.is_empty(), "spec paths with no route: {stale:?}");
+
+        for schema in ["JoinRequest", "GiftRequest", "StartResponse", "GameView", "GameEvent"] {
+            assert!(
+                spec["components"]["schemas"].get(schema).is_some(),
+                "{schema} missing from components"
+            );
+        }
+    }
+}
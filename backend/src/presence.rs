@@ -0,0 +1,110 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Live connection bookkeeping per game. Uses a std mutex so counts can be
+/// updated from `Drop` guards when sockets go away.
+#[derive(Default)]
+pub struct Presence {
+    games: Mutex<HashMap<String, GamePresence>>,
+}
+
+#[derive(Default)]
+struct GamePresence {
+    spectators: usize,
+    /// Open sockets per player; a player may have several tabs open.
+    players: HashMap<String, usize>,
+}
+
+impl GamePresence {
+    fn is_empty(&self) -> bool {
+        self.spectators == 0 && self.players.is_empty()
+    }
+}
+
+impl Presence {
+    pub fn spectators(&self, game_id: &str) -> usize {
+        let games = self.games.lock().expect("presence lock poisoned");
+        games.get(game_id).map(|g| g.spectators).unwrap_or(0)
+    }
+
+    /// Returns the new spectator count.
+    pub fn add_spectator(&self, game_id: &str) -> usize {
+        let mut games = self.games.lock().expect("presence lock poisoned");
+        let game = games.entry(game_id.to_string()).or_default();
+        game.spectators += 1;
+        game.spectators
+    }
+
+    /// Returns the new spectator count.
+    pub fn remove_spectator(&self, game_id: &str) -> usize {
+        let mut games = self.games.lock().expect("presence lock poisoned");
+        let Some(game) = games.get_mut(game_id) else {
+            return 0;
+        };
+        game.spectators = game.spectators.saturating_sub(1);
+        let count = game.spectators;
+        if game.is_empty() {
+            games.remove(game_id);
+        }
+        count
+    }
+
+    pub fn online_players(&self, game_id: &str) -> HashSet<String> {
+        let games = self.games.lock().expect("presence lock poisoned");
+        games
+            .get(game_id)
+            .map(|g| g.players.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns true when this is the player's first open socket.
+    pub fn player_connected(&self, game_id: &str, player_id: &str) -> bool {
+        let mut games = self.games.lock().expect("presence lock poisoned");
+        let game = games.entry(game_id.to_string()).or_default();
+        let sockets = game.players.entry(player_id.to_string()).or_insert(0);
+        *sockets += 1;
+        *sockets == 1
+    }
+
+    /// Returns true when the player's last socket closed.
+    pub fn player_disconnected(&self, game_id: &str, player_id: &str) -> bool {
+        let mut games = self.games.lock().expect("presence lock poisoned");
+        let Some(game) = games.get_mut(game_id) else {
+            return false;
+        };
+        let Some(sockets) = game.players.get_mut(player_id) else {
+            return false;
+        };
+        *sockets -= 1;
+        let offline = *sockets == 0;
+        if offline {
+            game.players.remove(player_id);
+        }
+        if game.is_empty() {
+            games.remove(game_id);
+        }
+        offline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn player_goes_offline_after_last_socket() {
+        let presence = Presence::default();
+        assert!(presence.player_connected("g", "p1"));
+        assert!(!presence.player_connected("g", "p1"));
+        presence.add_spectator("g");
+
+        assert!(!presence.player_disconnected("g", "p1"));
+        assert!(presence.online_players("g").contains("p1"));
+        assert!(presence.player_disconnected("g", "p1"));
+        assert!(presence.online_players("g").is_empty());
+        assert_eq!(presence.spectators("g"), 1);
+
+        assert_eq!(presence.remove_spectator("g"), 0);
+        assert!(presence.games.lock().unwrap().is_empty());
+    }
+}
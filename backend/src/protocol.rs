@@ -0,0 +1,78 @@
+//! Socket protocol versioning. A client's first frame must be a `hello`
+//! naming the protocol version it speaks; the server answers with a
+//! `welcome` listing the features both sides support, or closes the socket.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub use game_core::PROTOCOL_VERSION;
+
+/// Optional behaviour a client may ask for in its hello.
+pub const FEATURES: [&str; 2] = ["delta", "resync"];
+
+/// Frame encodings. These are fixed when the socket is opened (`?encoding=`
+/// or a subprotocol), so they are not negotiated in the hello.
+pub const ENCODINGS: [&str; 3] = ["json", "msgpack", "cbor"];
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionInfo {
+    pub protocol_version: u32,
+    pub server_version: &'static str,
+    pub features: [&'static str; 2],
+    pub encodings: [&'static str; 3],
+}
+
+impl VersionInfo {
+    pub fn current() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            server_version: env!("CARGO_PKG_VERSION"),
+            features: FEATURES,
+            encodings: ENCODINGS,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HandshakeError {
+    #[error("first message must be a hello")]
+    MissingHello,
+    #[error("no hello received in time")]
+    Timeout,
+    #[error("client speaks protocol {client}, server speaks {server}")]
+    IncompatibleVersion { client: u32, server: u32 },
+}
+
+/// Features from `requested` that this server supports, in server order.
+pub fn negotiate(protocol_version: u32, requested: &[String]) -> Result<Vec<String>, HandshakeError> {
+    if protocol_version != PROTOCOL_VERSION {
+        return Err(HandshakeError::IncompatibleVersion {
+            client: protocol_version,
+            server: PROTOCOL_VERSION,
+        });
+    }
+    Ok(FEATURES
+        .iter()
+        .filter(|feature| requested.iter().any(|r| r == *feature))
+        .map(|feature| feature.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiation_checks_version_and_intersects_features() {
+        let requested = vec![
+            "delta".to_string(),
+            "time-travel".to_string(),
+            "msgpack".to_string(),
+        ];
+        assert_eq!(negotiate(PROTOCOL_VERSION, &requested).unwrap(), vec!["delta"]);
+        assert!(matches!(
+            negotiate(PROTOCOL_VERSION + 1, &requested),
+            Err(HandshakeError::IncompatibleVersion { .. })
+        ));
+    }
+}
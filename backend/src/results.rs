@@ -0,0 +1,144 @@
+//! End-of-game summary: who went home with what. Built only from public
+//! fields of `GameRecord`, so the host token can never leak into an export.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::GameRecord;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultsFormat {
+    #[default]
+    Json,
+    Csv,
+    Md,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GameResults {
+    pub game_id: String,
+    pub results: Vec<ResultRow>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResultRow {
+    pub player_id: String,
+    pub player: String,
+    pub gift_id: Option<String>,
+    pub gift: Option<String>,
+    pub submitted_by: Option<String>,
+    pub product_url: Option<String>,
+    pub times_stolen: u8,
+}
+
+const COLUMNS: [&str; 5] = ["player", "gift", "submitted_by", "product_url", "times_stolen"];
+
+impl GameResults {
+    /// One row per player, in join order.
+    pub fn from_record(record: &GameRecord) -> Self {
+        let name_of = |id: &str| {
+            record
+                .players
+                .iter()
+                .find(|p| p.id == id)
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| id.to_string())
+        };
+
+        let results = record
+            .players
+            .iter()
+            .map(|player| {
+                let gift = record
+                    .gifts
+                    .iter()
+                    .find(|g| g.held_by.as_deref() == Some(player.id.as_str()));
+                ResultRow {
+                    player_id: player.id.clone(),
+                    player: player.name.clone(),
+                    gift_id: gift.map(|g| g.id.clone()),
+                    gift: gift.map(|g| g.title.clone().unwrap_or_else(|| g.hint.clone())),
+                    submitted_by: gift.map(|g| name_of(&g.submitted_by)),
+                    product_url: gift.map(|g| g.product_url.clone()),
+                    times_stolen: gift.map(|g| g.stolen_count).unwrap_or(0),
+                }
+            })
+            .collect();
+
+        Self {
+            game_id: record.id.clone(),
+            results,
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = COLUMNS.join(",");
+        out.push('\n');
+        for row in &self.results {
+            let fields = row.fields();
+            let escaped: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&escaped.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("| {} |\n", COLUMNS.join(" | "));
+        out.push_str(&format!("|{}\n", "---|".repeat(COLUMNS.len())));
+        for row in &self.results {
+            let fields = row.fields();
+            let escaped: Vec<String> = fields.iter().map(|f| md_cell(f)).collect();
+            out.push_str(&format!("| {} |\n", escaped.join(" | ")));
+        }
+        out
+    }
+}
+
+impl ResultRow {
+    fn fields(&self) -> [String; 5] {
+        [
+            self.player.clone(),
+            self.gift.clone().unwrap_or_default(),
+            self.submitted_by.clone().unwrap_or_default(),
+            self.product_url.clone().unwrap_or_default(),
+            self.times_stolen.to_string(),
+        ]
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    // Leading formula characters are neutralised so spreadsheets do not
+    // evaluate player-supplied text.
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn md_cell(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_and_markdown_escape_player_text() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!(csv_field("=HYPERLINK()"), "'=HYPERLINK()");
+        assert_eq!(md_cell("mug | socks\nset"), "mug \\| socks set");
+    }
+}
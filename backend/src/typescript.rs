@@ -0,0 +1,54 @@
+//! TypeScript declarations for the wire types, so the frontend does not have
+//! to hand-mirror serde's tag and rename rules. The checked-in copy lives in
+//! `bindings/protocol.d.ts`; regenerate it with
+//! `cargo run -p backend --bin gen-types > backend/bindings/protocol.d.ts`.
+
+use game_core::stats::{GiftTally, PlayerTally};
+use game_core::{GameEvent, GamePhase, GameStats, GiftState, PlayerAction, Rules};
+use ts_rs::TS;
+
+use crate::delta::PatchOp;
+use crate::{ClientMessage, GameView, GiftRecord, PlayerView, ServerMessage};
+
+pub fn definitions() -> String {
+    let decls = [
+        GamePhase::decl(),
+        GiftState::decl(),
+        GiftRecord::decl(),
+        PlayerView::decl(),
+        Rules::decl(),
+        GameView::decl(),
+        PlayerAction::decl(),
+        GameEvent::decl(),
+        GiftTally::decl(),
+        PlayerTally::decl(),
+        GameStats::decl(),
+        serde_json::Value::decl(),
+        PatchOp::decl(),
+        ServerMessage::decl(),
+        ClientMessage::decl(),
+    ];
+    let mut out = String::from("// Generated by `gen-types`. Do not edit by hand.\n");
+    for decl in decls {
+        out.push_str("\nexport ");
+        out.push_str(&decl);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_in_bindings_are_current() {
+        let checked_in = include_str!("../bindings/protocol.d.ts");
+        assert!(
+            definitions() == checked_in,
+            "bindings/protocol.d.ts is stale; regenerate it with \
+             `cargo run -p backend --bin gen-types > backend/bindings/protocol.d.ts` \
+             and review the diff"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Thin HTTP and WebSocket client for the backend's public API.
+
+use futures::{SinkExt, StreamExt};
+use game_core::{PlayerAction, PROTOCOL_VERSION};
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("server returned {status}: {body}")]
+    Server { status: StatusCode, body: String },
+    #[error("socket error: {0}")]
+    Socket(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("server refused the socket: {0}")]
+    Refused(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedGame {
+    pub game_id: String,
+    pub host_token: String,
+    pub spectator_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GiftSubmission {
+    pub player_id: String,
+    pub product_url: String,
+    pub hint: String,
+    pub image_url: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Joined {
+    player_id: String,
+}
+
+/// Which socket to tail.
+pub enum Watch {
+    Player(String),
+    Spectator { token: String },
+}
+
+pub struct Client {
+    http: reqwest::Client,
+    base: String,
+}
+
+impl Client {
+    pub fn new(base: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base: base.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn create_game(&self, admin_password: &str) -> Result<CreatedGame, CliError> {
+        let res = self
+            .http
+            .post(format!("{}/game", self.base))
+            .header("x-admin-password", admin_password)
+            .send()
+            .await?;
+        Ok(check(res).await?.json().await?)
+    }
+
+    /// Returns the new player's id.
+    pub async fn join(&self, game_id: &str, name: &str) -> Result<String, CliError> {
+        let res = self
+            .http
+            .post(format!("{}/game/{game_id}/join", self.base))
+            .json(&json!({ "name": name }))
+            .send()
+            .await?;
+        let joined: Joined = check(res).await?.json().await?;
+        Ok(joined.player_id)
+    }
+
+    /// Returns the stored gift, including its id.
+    pub async fn submit_gift(
+        &self,
+        game_id: &str,
+        gift: &GiftSubmission,
+    ) -> Result<Value, CliError> {
+        let res = self
+            .http
+            .post(format!("{}/game/{game_id}/gift", self.base))
+            .json(gift)
+            .send()
+            .await?;
+        let body: Value = check(res).await?.json().await?;
+        Ok(body["gift"].clone())
+    }
+
+    pub async fn start(
+        &self,
+        game_id: &str,
+        host_token: &str,
+        seed: Option<u64>,
+    ) -> Result<Value, CliError> {
+        let mut req = self
+            .http
+            .post(format!("{}/game/{game_id}/start", self.base))
+            .header("x-host-token", host_token);
+        if let Some(seed) = seed {
+            req = req.query(&[("seed", seed)]);
+        }
+        Ok(check(req.send().await?).await?.json().await?)
+    }
+
+    pub async fn act(&self, game_id: &str, action: PlayerAction) -> Result<(), CliError> {
+        let res = self
+            .http
+            .post(format!("{}/game/{game_id}/action", self.base))
+            .json(&json!({ "player_id": action.player_id(), "action": action }))
+            .send()
+            .await?;
+        check(res).await?;
+        Ok(())
+    }
+
+    pub async fn game(&self, game_id: &str) -> Result<Value, CliError> {
+        let res = self
+            .http
+            .get(format!("{}/game/{game_id}", self.base))
+            .send()
+            .await?;
+        Ok(check(res).await?.json().await?)
+    }
+
+    /// Open a socket, complete the handshake and hand every server message
+    /// to `each` until it returns false or the socket closes.
+    pub async fn tail(
+        &self,
+        game_id: &str,
+        watch: &Watch,
+        mut each: impl FnMut(Value) -> bool,
+    ) -> Result<(), CliError> {
+        let ws_base = self.base.replacen("http", "ws", 1);
+        let url = match watch {
+            Watch::Player(player_id) => format!("{ws_base}/ws/{game_id}/{player_id}"),
+            Watch::Spectator { token } => format!("{ws_base}/ws/{game_id}/spectate?token={token}"),
+        };
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await?;
+        let hello = json!({ "type": "hello", "protocol_version": PROTOCOL_VERSION });
+        socket.send(Message::Text(hello.to_string())).await?;
+
+        while let Some(msg) = socket.next().await {
+            let text = match msg? {
+                Message::Text(text) => text,
+                Message::Close(frame) => {
+                    return match frame {
+                        Some(frame) if !frame.reason.is_empty() => {
+                            Err(CliError::Refused(frame.reason.to_string()))
+                        }
+                        _ => Ok(()),
+                    };
+                }
+                _ => continue,
+            };
+            // Errors and rejections arrive as plain `error:...` text.
+            let value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+            if !each(value) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn check(res: Response) -> Result<Response, CliError> {
+    let status = res.status();
+    if status.is_success() {
+        return Ok(res);
+    }
+    let body = res.text().await.unwrap_or_default();
+    Err(CliError::Server { status, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn serve() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = backend::app(backend::AppState::default());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn plays_a_game_end_to_end() {
+        let client = Client::new(serve().await);
+        assert!(matches!(
+            client.create_game("wrong").await,
+            Err(CliError::Server {
+                status: StatusCode::UNAUTHORIZED,
+                ..
+            })
+        ));
+        let created = client.create_game("changeme").await.unwrap();
+        let game_id = &created.game_id;
+
+        let mut gifts = Vec::new();
+        for name in ["alice", "bob"] {
+            let player_id = client.join(game_id, name).await.unwrap();
+            let gift = client
+                .submit_gift(
+                    game_id,
+                    &GiftSubmission {
+                        player_id,
+                        product_url: format!("https://example.com/{name}"),
+                        hint: format!("from {name}"),
+                        image_url: None,
+                        title: None,
+                    },
+                )
+                .await
+                .unwrap();
+            gifts.push(gift["id"].as_str().unwrap().to_string());
+        }
+
+        let started = client
+            .start(game_id, &created.host_token, Some(7))
+            .await
+            .unwrap();
+        let turn_order: Vec<String> =
+            serde_json::from_value(started["turn_order"].clone()).unwrap();
+
+        let (subscribed, ready) = tokio::sync::oneshot::channel();
+        let tail = {
+            let client = Client::new(client.base.clone());
+            let game_id = game_id.clone();
+            let token = created.spectator_token.clone();
+            let mut subscribed = Some(subscribed);
+            tokio::spawn(async move {
+                let mut seen = Vec::new();
+                client
+                    .tail(&game_id, &Watch::Spectator { token }, |msg| {
+                        // The first state goes out once the socket is
+                        // subscribed, so nothing after it can be missed.
+                        if msg["type"] == "state" {
+                            if let Some(subscribed) = subscribed.take() {
+                                let _ = subscribed.send(());
+                            }
+                        }
+                        let done = msg["event"]["type"] == "game_finished";
+                        seen.push(msg);
+                        !done
+                    })
+                    .await
+                    .map(|()| seen)
+            })
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), ready)
+            .await
+            .expect("spectator subscribed")
+            .unwrap();
+
+        let rejected = client
+            .act(
+                game_id,
+                PlayerAction::ChooseGift {
+                    player_id: turn_order[1].clone(),
+                    gift_id: gifts[0].clone(),
+                },
+            )
+            .await;
+        assert!(matches!(
+            rejected,
+            Err(CliError::Server {
+                status: StatusCode::CONFLICT,
+                ..
+            })
+        ));
+
+        for (player_id, gift_id) in turn_order.iter().zip(&gifts) {
+            client
+                .act(
+                    game_id,
+                    PlayerAction::ChooseGift {
+                        player_id: player_id.clone(),
+                        gift_id: gift_id.clone(),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let seen = tokio::time::timeout(std::time::Duration::from_secs(5), tail)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(seen[0]["type"], "welcome");
+        assert!(seen.iter().any(|m| m["event"]["type"] == "gift_opened"));
+        assert_eq!(client.game(game_id).await.unwrap()["phase"], "finished");
+    }
+}
@@ -0,0 +1,3 @@
+//! Client library behind `elephant-cli`, shared with other terminal tools.
+
+pub mod client;
@@ -0,0 +1,171 @@
+//! Command-line host tool and scripted smoke test for the game server.
+
+use clap::{Parser, Subcommand};
+use elephant_cli::client::{CliError, Client, GiftSubmission, Watch};
+use game_core::PlayerAction;
+use serde_json::Value;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "elephant-cli",
+    about = "Drive a white elephant server from the terminal"
+)]
+struct Cli {
+    /// Base URL of the backend.
+    #[arg(long, env = "ELEPHANT_SERVER", default_value = "http://localhost:3000")]
+    server: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create a game; prints its id and tokens.
+    Create {
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        admin_password: String,
+    },
+    /// Join a game; prints the new player id.
+    Join { game_id: String, name: String },
+    /// Submit or replace a player's gift.
+    Gift {
+        game_id: String,
+        #[arg(long)]
+        player: String,
+        #[arg(long)]
+        url: String,
+        #[arg(long)]
+        hint: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        image_url: Option<String>,
+    },
+    /// Close submissions and deal the turn order.
+    Start {
+        game_id: String,
+        #[arg(long, env = "ELEPHANT_HOST_TOKEN", hide_env_values = true)]
+        host_token: String,
+        /// Fixed seed for a reproducible turn order.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Open a wrapped gift.
+    Open {
+        game_id: String,
+        #[arg(long)]
+        player: String,
+        gift_id: String,
+    },
+    /// Steal an opened gift.
+    Steal {
+        game_id: String,
+        #[arg(long)]
+        player: String,
+        gift_id: String,
+    },
+    /// Print the current game view.
+    State { game_id: String },
+    /// Print every socket message as one JSON line until the game ends.
+    Tail {
+        game_id: String,
+        /// Watch as this player (also marks them online).
+        #[arg(
+            long,
+            conflicts_with = "spectator_token",
+            required_unless_present = "spectator_token"
+        )]
+        player: Option<String>,
+        #[arg(long, env = "ELEPHANT_SPECTATOR_TOKEN", hide_env_values = true)]
+        spectator_token: Option<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(Client::new(cli.server), cli.command).await {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(client: Client, command: Command) -> Result<(), CliError> {
+    match command {
+        Command::Create { admin_password } => {
+            let created = client.create_game(&admin_password).await?;
+            print_json(&serde_json::to_value(created).expect("serializable"));
+        }
+        Command::Join { game_id, name } => println!("{}", client.join(&game_id, &name).await?),
+        Command::Gift {
+            game_id,
+            player,
+            url,
+            hint,
+            title,
+            image_url,
+        } => {
+            let gift = GiftSubmission {
+                player_id: player,
+                product_url: url,
+                hint,
+                image_url,
+                title,
+            };
+            print_json(&client.submit_gift(&game_id, &gift).await?);
+        }
+        Command::Start {
+            game_id,
+            host_token,
+            seed,
+        } => print_json(&client.start(&game_id, &host_token, seed).await?),
+        Command::Open {
+            game_id,
+            player,
+            gift_id,
+        } => {
+            let action = PlayerAction::ChooseGift {
+                player_id: player,
+                gift_id,
+            };
+            client.act(&game_id, action).await?;
+        }
+        Command::Steal {
+            game_id,
+            player,
+            gift_id,
+        } => {
+            let action = PlayerAction::StealGift {
+                player_id: player,
+                gift_id,
+            };
+            client.act(&game_id, action).await?;
+        }
+        Command::State { game_id } => print_json(&client.game(&game_id).await?),
+        Command::Tail {
+            game_id,
+            player,
+            spectator_token,
+        } => {
+            let watch = match (player, spectator_token) {
+                (Some(player), _) => Watch::Player(player),
+                (None, Some(token)) => Watch::Spectator { token },
+                (None, None) => unreachable!("clap requires one of them"),
+            };
+            client
+                .tail(&game_id, &watch, |msg| {
+                    println!("{msg}");
+                    msg["event"]["type"] != "game_finished"
+                })
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("serializable")
+    );
+}
@@ -0,0 +1,116 @@
+//! Monte Carlo runs of whole games, for picking house rules.
+
+mod sim;
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use game_core::StrategyKind;
+use sim::{Report, Scenario};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "elephant-sim",
+    about = "Simulate white elephant games under different house rules"
+)]
+struct Cli {
+    /// Games played per scenario.
+    #[arg(long, default_value_t = 10_000)]
+    games: usize,
+    /// Table sizes to try.
+    #[arg(long, value_delimiter = ',', default_value = "4,8,12")]
+    players: Vec<usize>,
+    /// Steal limits per gift to try.
+    #[arg(long, value_delimiter = ',', default_value = "3")]
+    max_steals: Vec<u8>,
+    /// How everyone at the table plays: random, greedy or always_open.
+    #[arg(long, value_delimiter = ',', default_value = "random,greedy", value_parser = parse_strategy)]
+    strategy: Vec<StrategyKind>,
+    /// Seed for reproducible runs.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+fn parse_strategy(name: &str) -> Result<StrategyKind, String> {
+    StrategyKind::ALL
+        .into_iter()
+        .find(|kind| kind.name() == name)
+        .ok_or_else(|| format!("unknown strategy {name:?}"))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    for &players in &cli.players {
+        for &max_steals in &cli.max_steals {
+            for &strategy in &cli.strategy {
+                let scenario = Scenario {
+                    players,
+                    max_steals,
+                    strategy,
+                };
+                match sim::run(scenario, cli.games, cli.seed) {
+                    Ok(report) => print_report(scenario, &report),
+                    Err(err) => {
+                        eprintln!("{scenario:?}: {err}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn print_report(scenario: Scenario, report: &Report) {
+    println!(
+        "{} players, {} steals per gift, {} ({} games)",
+        scenario.players,
+        scenario.max_steals,
+        scenario.strategy.name(),
+        report.games
+    );
+    if report.stalled > 0 {
+        println!("  stalled       {} games", report.stalled);
+    }
+    println!(
+        "  game length   avg {:.2} moves, longest {}",
+        report.average_moves(),
+        report.longest_game
+    );
+    println!("  steals/gift   {}", histogram(&report.steals_per_gift, 0));
+    println!(
+        "  steals        avg {:.2} per game; longest chain avg {:.2}, max {}; {}",
+        report.average_steals(),
+        report.average_longest_chain(),
+        report.longest_chain(),
+        histogram(&report.longest_chains, 0)
+    );
+    if report.contested == 0 {
+        println!("  first player  no gift was ever stolen");
+    } else {
+        println!(
+            "  first player  ends with a most-stolen gift in {:.1}% of {} contested games, \
+             vs {:.1}% for an average seat ({:.2}x)",
+            report.first_player_rate() * 100.0,
+            report.contested,
+            report.any_seat_rate() * 100.0,
+            report.first_player_advantage()
+        );
+    }
+    println!();
+}
+
+/// `n: share%` for each bucket from `from` on.
+fn histogram(counts: &[usize], from: usize) -> String {
+    let total: usize = counts.iter().skip(from).sum();
+    if total == 0 {
+        return "none".to_string();
+    }
+    counts
+        .iter()
+        .enumerate()
+        .skip(from)
+        .map(|(n, &count)| format!("{n}: {:.1}%", count as f64 * 100.0 / total as f64))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
@@ -0,0 +1,249 @@
+//! Plays whole games through `game_core::apply_action` and tallies what
+//! happened, so house rules can be compared before a party.
+
+use game_core::{
+    apply_action, Game, GameError, GamePhase, GameStats, PlayerAction, Rules, StrategyKind,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// One combination of house rules and play style.
+#[derive(Debug, Clone, Copy)]
+pub struct Scenario {
+    pub players: usize,
+    pub max_steals: u8,
+    /// Everyone at the table plays this way.
+    pub strategy: StrategyKind,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub games: usize,
+    /// Games where a strategy had no move for the active player. Should
+    /// stay zero; anything else is a rules bug worth chasing.
+    pub stalled: usize,
+    pub total_moves: usize,
+    pub longest_game: usize,
+    /// Index is how many times a gift was stolen, value is how many gifts.
+    pub steals_per_gift: Vec<usize>,
+    pub total_steals: usize,
+    /// Index is a game's longest run of steals in a row, value is how many
+    /// games.
+    pub longest_chains: Vec<usize>,
+    /// Finished games where at least one gift was stolen. Without a steal
+    /// every gift ties for most stolen, which says nothing about any seat.
+    pub contested: usize,
+    /// Contested games the first player finished holding one of the
+    /// most-stolen gifts.
+    pub first_player_top: usize,
+    /// Same, summed over every seat and divided by the table size, as the
+    /// baseline the first player is compared against.
+    pub any_seat_top: f64,
+}
+
+impl Report {
+    pub fn average_moves(&self) -> f64 {
+        ratio(self.total_moves, self.games)
+    }
+
+    pub fn average_steals(&self) -> f64 {
+        ratio(self.total_steals, self.games - self.stalled)
+    }
+
+    /// Average over finished games of each game's longest chain.
+    pub fn average_longest_chain(&self) -> f64 {
+        let weighted: usize = self
+            .longest_chains
+            .iter()
+            .enumerate()
+            .map(|(len, n)| len * n)
+            .sum();
+        ratio(weighted, self.longest_chains.iter().sum())
+    }
+
+    pub fn longest_chain(&self) -> usize {
+        self.longest_chains.iter().rposition(|&n| n > 0).unwrap_or(0)
+    }
+
+    pub fn first_player_rate(&self) -> f64 {
+        ratio(self.first_player_top, self.contested)
+    }
+
+    pub fn any_seat_rate(&self) -> f64 {
+        if self.contested == 0 {
+            0.0
+        } else {
+            self.any_seat_top / self.contested as f64
+        }
+    }
+
+    /// How much more often than an average seat the first player ends up
+    /// with a most-stolen gift; 1.0 means no advantage.
+    pub fn first_player_advantage(&self) -> f64 {
+        let baseline = self.any_seat_rate();
+        if baseline == 0.0 {
+            0.0
+        } else {
+            self.first_player_rate() / baseline
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SimError {
+    /// A strategy picked a move the rules refused. Replaying `game_seed` with
+    /// the same scenario reproduces it.
+    #[error("game seed {game_seed}: {action:?} was refused: {source}")]
+    IllegalMove {
+        game_seed: u64,
+        action: PlayerAction,
+        source: GameError,
+    },
+}
+
+/// Play `games` games of `scenario`. The same `seed` gives the same report.
+pub fn run(scenario: Scenario, games: usize, seed: u64) -> Result<Report, SimError> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut report = Report {
+        steals_per_gift: vec![0; usize::from(scenario.max_steals) + 1],
+        ..Report::default()
+    };
+    for _ in 0..games {
+        play(scenario, rng.gen(), &mut report)?;
+    }
+    Ok(report)
+}
+
+/// Play one game, seeded on its own so a failure can be replayed alone.
+fn play(scenario: Scenario, game_seed: u64, report: &mut Report) -> Result<(), SimError> {
+    let mut rng = ChaCha8Rng::seed_from_u64(game_seed);
+    let mut game = Game::table(scenario.players, &mut rng);
+    game.rules = Rules {
+        max_steals_per_gift: scenario.max_steals,
+    };
+    let mut strategies: Vec<_> = game
+        .turn_order
+        .iter()
+        .map(|id| {
+            let strategy = scenario
+                .strategy
+                .build(ChaCha8Rng::seed_from_u64(rng.gen()));
+            (id.clone(), strategy)
+        })
+        .collect();
+
+    let mut moves = 0;
+    while let Some(active) = game.active_player.clone() {
+        let (_, strategy) = strategies
+            .iter_mut()
+            .find(|(id, _)| *id == active)
+            .expect("every player has a strategy");
+        let Some(action) = strategy.choose(&game, &active) else {
+            break;
+        };
+        if let Err(source) = apply_action(&mut game, action.clone()) {
+            return Err(SimError::IllegalMove {
+                game_seed,
+                action,
+                source,
+            });
+        }
+        moves += 1;
+    }
+
+    report.games += 1;
+    if game.phase != GamePhase::Finished {
+        report.stalled += 1;
+        return Ok(());
+    }
+    report.total_moves += moves;
+    report.longest_game = report.longest_game.max(moves);
+    tally(&game, report);
+    Ok(())
+}
+
+fn tally(game: &Game, report: &mut Report) {
+    for gift in &game.gifts {
+        report.steals_per_gift[usize::from(gift.stolen_count)] += 1;
+    }
+
+    let stats = GameStats::from_history(&game.history);
+    report.total_steals += stats.total_steals;
+    let chain = stats.longest_steal_chain;
+    if report.longest_chains.len() <= chain {
+        report.longest_chains.resize(chain + 1, 0);
+    }
+    report.longest_chains[chain] += 1;
+
+    let top = game.gifts.iter().map(|g| g.stolen_count).max().unwrap_or(0);
+    if top == 0 {
+        return;
+    }
+    report.contested += 1;
+    let holds_top = |player: &str| {
+        game.gifts
+            .iter()
+            .any(|g| g.held_by.as_deref() == Some(player) && g.stolen_count == top)
+    };
+    if holds_top(&game.turn_order[0]) {
+        report.first_player_top += 1;
+    }
+    let seats = game.turn_order.iter().filter(|p| holds_top(p)).count();
+    report.any_seat_top += seats as f64 / game.turn_order.len() as f64;
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tallies_are_consistent_and_reproducible() {
+        let opener = Scenario {
+            players: 6,
+            max_steals: 3,
+            strategy: StrategyKind::AlwaysOpen,
+        };
+        let report = run(opener, 50, 1).unwrap();
+        assert_eq!(report.stalled, 0);
+        assert_eq!(report.average_moves(), 6.0);
+        assert_eq!(report.steals_per_gift, vec![300, 0, 0, 0]);
+        assert_eq!(report.total_steals, 0);
+        assert_eq!(report.longest_chains, vec![50]);
+        // Nobody steals, so no game says anything about the first player.
+        assert_eq!(report.contested, 0);
+        assert_eq!(report.first_player_rate(), 0.0);
+
+        for strategy in [StrategyKind::Greedy, StrategyKind::Random] {
+            let scenario = Scenario { strategy, ..opener };
+            let report = run(scenario, 100, 9).unwrap();
+            assert_eq!(report.stalled, 0);
+            assert_eq!(report.steals_per_gift.iter().sum::<usize>(), 100 * 6);
+            // Gift counters and the event history agree on the steals.
+            let steals: usize = report
+                .steals_per_gift
+                .iter()
+                .enumerate()
+                .map(|(n, g)| n * g)
+                .sum();
+            assert_eq!(report.total_steals, steals);
+            assert_eq!(report.total_moves, 100 * 6 + steals);
+            assert_eq!(report.longest_chains.iter().sum::<usize>(), 100);
+            assert!(report.longest_chain() <= 6 * 3);
+            assert!(report.contested > 0 && report.contested <= 100);
+            assert!(report.first_player_top <= report.contested);
+            assert!((0.0..=1.0).contains(&report.any_seat_rate()));
+
+            let again = run(scenario, 100, 9).unwrap();
+            assert_eq!(again.steals_per_gift, report.steals_per_gift);
+            assert_eq!(again.first_player_top, report.first_player_top);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! What the projector shows, folded from the spectator socket's messages.
+//! Kept free of terminal code so it can be driven by tests.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use game_core::{GameEvent, GamePhase, GiftState};
+use serde::Deserialize;
+
+/// How many ticker lines are kept.
+const TICKER_LEN: usize = 8;
+/// How long a gift card flashes after it is opened or stolen.
+pub const FLASH_FOR: Duration = Duration::from_millis(1500);
+/// Half a blink.
+const BLINK: Duration = Duration::from_millis(250);
+
+/// The parts of `ServerMessage` the board cares about.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Incoming {
+    State(View),
+    Event {
+        event: GameEvent,
+    },
+    Spectators {
+        count: usize,
+    },
+    PresenceChanged {
+        player_id: String,
+        online: bool,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Mirror of the server's `GameView`, minus what a spectator never sees.
+#[derive(Debug, Deserialize)]
+pub struct View {
+    pub phase: GamePhase,
+    pub players: Vec<Seat>,
+    pub gifts: Vec<Card>,
+    pub active_player: Option<String>,
+    #[serde(default)]
+    pub spectators: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Seat {
+    pub id: String,
+    pub name: String,
+    pub online: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Card {
+    pub id: String,
+    pub hint: String,
+    pub title: Option<String>,
+    pub held_by: Option<String>,
+    pub stolen_count: u8,
+    pub state: GiftState,
+}
+
+impl Card {
+    /// The title once unwrapped, the hint until then.
+    pub fn label(&self) -> &str {
+        match (&self.state, &self.title) {
+            (GiftState::Opened, Some(title)) if !title.is_empty() => title,
+            _ => &self.hint,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashKind {
+    Opened,
+    Stolen,
+}
+
+#[derive(Debug)]
+struct Flash {
+    gift_id: String,
+    kind: FlashKind,
+    started: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct Board {
+    pub view: Option<View>,
+    /// Newest first.
+    pub ticker: VecDeque<String>,
+    flashes: Vec<Flash>,
+}
+
+impl Board {
+    pub fn apply(&mut self, msg: Incoming, now: Instant) {
+        match msg {
+            Incoming::State(view) => self.view = Some(view),
+            Incoming::Event { event } => self.on_event(event, now),
+            Incoming::Spectators { count } => {
+                if let Some(view) = self.view.as_mut() {
+                    view.spectators = count;
+                }
+            }
+            Incoming::PresenceChanged { player_id, online } => {
+                if let Some(seat) = self
+                    .view
+                    .as_mut()
+                    .and_then(|v| v.players.iter_mut().find(|p| p.id == player_id))
+                {
+                    seat.online = online;
+                }
+            }
+            Incoming::Other => {}
+        }
+    }
+
+    /// Add a line to the ticker that did not come from the game itself.
+    pub fn note(&mut self, line: impl Into<String>) {
+        self.ticker.push_front(line.into());
+        self.ticker.truncate(TICKER_LEN);
+    }
+
+    /// Drop flashes that have run their course.
+    pub fn tick(&mut self, now: Instant) {
+        self.flashes
+            .retain(|f| now.saturating_duration_since(f.started) < FLASH_FOR);
+    }
+
+    /// The flash on `gift_id`, if any, and whether it is lit at `now`.
+    pub fn flash(&self, gift_id: &str, now: Instant) -> Option<(FlashKind, bool)> {
+        let flash = self.flashes.iter().rev().find(|f| f.gift_id == gift_id)?;
+        let elapsed = now.saturating_duration_since(flash.started);
+        if elapsed >= FLASH_FOR {
+            return None;
+        }
+        let lit = (elapsed.as_millis() / BLINK.as_millis()).is_multiple_of(2);
+        Some((flash.kind, lit))
+    }
+
+    pub fn name<'a>(&'a self, player_id: &'a str) -> &'a str {
+        self.view
+            .as_ref()
+            .and_then(|v| v.players.iter().find(|p| p.id == player_id))
+            .map_or(player_id, |p| p.name.as_str())
+    }
+
+    fn gift_label(&self, gift_id: &str) -> String {
+        self.view
+            .as_ref()
+            .and_then(|v| v.gifts.iter().find(|g| g.id == gift_id))
+            .map_or_else(|| "a gift".to_string(), |g| format!("\"{}\"", g.label()))
+    }
+
+    fn on_event(&mut self, event: GameEvent, now: Instant) {
+        let line = match &event {
+            GameEvent::GiftOpened { player_id, gift_id } => {
+                self.start_flash(gift_id, FlashKind::Opened, now);
+                format!(
+                    "{} opened {}",
+                    self.name(player_id),
+                    self.gift_label(gift_id)
+                )
+            }
+            GameEvent::GiftStolen { from, to, gift_id } => {
+                self.start_flash(gift_id, FlashKind::Stolen, now);
+                format!(
+                    "{} stole {} from {}",
+                    self.name(to),
+                    self.gift_label(gift_id),
+                    self.name(from)
+                )
+            }
+            GameEvent::TurnChanged { player_id } => format!("{} is up", self.name(player_id)),
+            GameEvent::GameFinished => "Game over!".to_string(),
+        };
+        self.note(line);
+    }
+
+    fn start_flash(&mut self, gift_id: &str, kind: FlashKind, now: Instant) {
+        self.flashes.push(Flash {
+            gift_id: gift_id.to_string(),
+            kind,
+            started: now,
+        });
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn incoming(value: serde_json::Value) -> Incoming {
+        serde_json::from_value(value).unwrap()
+    }
+
+    pub(crate) fn sample_state() -> serde_json::Value {
+        json!({
+            "type": "state",
+            "id": "g",
+            "phase": "in_progress",
+            "players": [
+                { "id": "p1", "name": "alice", "joined_at": 1, "online": true },
+                { "id": "p2", "name": "bob", "joined_at": 2, "online": false }
+            ],
+            "gifts": [
+                { "id": "g1", "submitted_by": "p2", "product_url": "u", "hint": "soft",
+                  "image_url": null, "title": "Socks", "opened_by": "p1", "held_by": "p2",
+                  "stolen_count": 1, "state": "opened" },
+                { "id": "g2", "submitted_by": "", "product_url": "", "hint": "heavy",
+                  "image_url": null, "title": null, "opened_by": null, "held_by": null,
+                  "stolen_count": 0, "state": "unopened" }
+            ],
+            "turn_order": ["p1", "p2"],
+            "active_player": "p1",
+            "spectators": 1
+        })
+    }
+
+    #[test]
+    fn events_fill_the_ticker_and_flash_their_gift() {
+        let start = Instant::now();
+        let mut board = Board::default();
+        board.apply(
+            incoming(json!({
+                "type": "welcome",
+                "protocol_version": game_core::PROTOCOL_VERSION,
+                "features": []
+            })),
+            start,
+        );
+        board.apply(incoming(sample_state()), start);
+        board.apply(
+            incoming(json!({ "type": "presence_changed", "player_id": "p2", "online": true })),
+            start,
+        );
+        board.apply(
+            incoming(json!({ "type": "event", "event": { "type": "gift_stolen", "from": "p1", "to": "p2", "gift_id": "g1" } })),
+            start,
+        );
+
+        assert!(board.view.as_ref().unwrap().players[1].online);
+        assert_eq!(board.ticker[0], "bob stole \"Socks\" from alice");
+        assert_eq!(board.flash("g1", start), Some((FlashKind::Stolen, true)));
+        assert_eq!(
+            board.flash("g1", start + BLINK),
+            Some((FlashKind::Stolen, false))
+        );
+        assert_eq!(board.flash("g2", start), None);
+
+        board.tick(start + FLASH_FOR);
+        assert_eq!(board.flash("g1", start + FLASH_FOR), None);
+
+        for _ in 0..TICKER_LEN {
+            board.apply(
+                incoming(json!({ "type": "event", "event": { "type": "game_finished" } })),
+                start,
+            );
+        }
+        assert_eq!(board.ticker.len(), TICKER_LEN);
+    }
+}
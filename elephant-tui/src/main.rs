@@ -0,0 +1,96 @@
+//! Big-screen spectator view for rooms with a laptop and a projector.
+
+mod board;
+mod ui;
+
+use std::time::{Duration, Instant};
+
+use board::{Board, Incoming};
+use clap::Parser;
+use elephant_cli::client::{Client, Watch};
+use ratatui::crossterm::event::{self, Event, KeyCode};
+use ratatui::DefaultTerminal;
+use serde_json::Value;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+
+/// Redraw often enough for the flashes to blink smoothly.
+const FRAME: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "elephant-tui",
+    about = "Watch a white elephant game in the terminal"
+)]
+struct Cli {
+    /// Base URL of the backend.
+    #[arg(long, env = "ELEPHANT_SERVER", default_value = "http://localhost:3000")]
+    server: String,
+    game_id: String,
+    #[arg(long, env = "ELEPHANT_SPECTATOR_TOKEN", hide_env_values = true)]
+    spectator_token: String,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let runtime = tokio::runtime::Runtime::new().expect("tokio runtime");
+    let (tx, rx) = mpsc::unbounded_channel();
+    let feed = runtime.spawn(async move {
+        let watch = Watch::Spectator {
+            token: cli.spectator_token,
+        };
+        Client::new(cli.server)
+            .tail(&cli.game_id, &watch, |msg| tx.send(msg).is_ok())
+            .await
+    });
+
+    let mut terminal = ratatui::init();
+    let drawn = run(&mut terminal, rx);
+    ratatui::restore();
+
+    if let Err(err) = drawn {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+    if feed.is_finished() {
+        if let Ok(Err(err)) = runtime.block_on(feed) {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Draw until the user presses `q` or Esc.
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut rx: mpsc::UnboundedReceiver<Value>,
+) -> std::io::Result<()> {
+    let mut board = Board::default();
+    let mut connected = true;
+    loop {
+        let now = Instant::now();
+        while connected {
+            match rx.try_recv() {
+                Ok(msg) => match serde_json::from_value::<Incoming>(msg) {
+                    Ok(msg) => board.apply(msg, now),
+                    // `error:...` text and anything newer than this build.
+                    Err(_) => continue,
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    board.note("connection closed; press q to quit");
+                    connected = false;
+                }
+            }
+        }
+        board.tick(now);
+        terminal.draw(|frame| ui::draw(frame, &board, now))?;
+
+        if event::poll(FRAME)? {
+            if let Event::Key(key) = event::read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
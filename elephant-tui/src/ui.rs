@@ -0,0 +1,151 @@
+//! Draws a `Board` onto the terminal.
+
+use std::time::Instant;
+
+use game_core::{GamePhase, GiftState};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+
+use crate::board::{Board, Card, FlashKind};
+
+const CARD_WIDTH: u16 = 26;
+const CARD_HEIGHT: u16 = 5;
+
+pub fn draw(frame: &mut Frame, board: &Board, now: Instant) {
+    let [header, gifts, ticker] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(CARD_HEIGHT),
+        Constraint::Length(10),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Paragraph::new(status_line(board)).block(Block::bordered().title(" White Elephant ")),
+        header,
+    );
+    draw_gifts(frame, board, gifts, now);
+
+    let lines: Vec<Line> = board
+        .ticker
+        .iter()
+        .map(|l| Line::from(l.as_str()))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" What happened ")),
+        ticker,
+    );
+}
+
+fn status_line(board: &Board) -> String {
+    let Some(view) = &board.view else {
+        return "Waiting for the game...".to_string();
+    };
+    let phase = match view.phase {
+        GamePhase::Lobby => "Lobby",
+        GamePhase::Submissions => "Collecting gifts",
+        GamePhase::InProgress => "In progress",
+        GamePhase::Finished => "Finished",
+    };
+    let mut line = phase.to_string();
+    if let Some(active) = &view.active_player {
+        line.push_str(&format!("  |  Up now: {}", board.name(active)));
+    }
+    let online = view.players.iter().filter(|p| p.online).count();
+    line.push_str(&format!(
+        "  |  {online}/{} players online  |  {} watching",
+        view.players.len(),
+        view.spectators
+    ));
+    line
+}
+
+fn draw_gifts(frame: &mut Frame, board: &Board, area: Rect, now: Instant) {
+    let Some(view) = &board.view else { return };
+    let columns = (area.width / CARD_WIDTH).max(1);
+    for (index, card) in view.gifts.iter().enumerate() {
+        let (col, row) = (index as u16 % columns, index as u16 / columns);
+        let rect = Rect {
+            x: area.x + col * CARD_WIDTH,
+            y: area.y + row * CARD_HEIGHT,
+            width: CARD_WIDTH.min(area.width),
+            height: CARD_HEIGHT,
+        };
+        // Cards that do not fit are dropped rather than squashed.
+        if rect.bottom() > area.bottom() {
+            break;
+        }
+        frame.render_widget(gift_card(board, card, now), rect);
+    }
+}
+
+fn gift_card<'a>(board: &'a Board, card: &'a Card, now: Instant) -> Paragraph<'a> {
+    let (title, holder) = match card.state {
+        GiftState::Unopened => (" wrapped ".to_string(), "hint: ".to_string() + &card.hint),
+        GiftState::Opened => (
+            format!(" {} ", card.label()),
+            format!(
+                "held by {}",
+                card.held_by.as_deref().map_or("nobody", |h| board.name(h))
+            ),
+        ),
+    };
+    let steals = format!("stolen {}x", card.stolen_count);
+
+    let mut style = Style::default();
+    if card.state == GiftState::Unopened {
+        style = style.fg(Color::DarkGray);
+    }
+    match board.flash(&card.id, now) {
+        Some((FlashKind::Opened, true)) => {
+            style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        }
+        Some((FlashKind::Stolen, true)) => {
+            style = style.fg(Color::Red).add_modifier(Modifier::BOLD)
+        }
+        _ => {}
+    }
+
+    Paragraph::new(vec![Line::from(holder), Line::from(steals)])
+        .block(Block::bordered().title(title))
+        .style(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::tests::sample_state;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn renders_turn_board_and_ticker() {
+        let now = Instant::now();
+        let mut board = Board::default();
+        board.apply(serde_json::from_value(sample_state()).unwrap(), now);
+        board.note("alice is up");
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|frame| draw(frame, &board, now)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+
+        for expected in [
+            "Up now: alice",
+            "Socks",
+            "held by bob",
+            "stolen 1x",
+            "hint: heavy",
+            "alice is up",
+        ] {
+            assert!(screen.contains(expected), "missing {expected:?}");
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Any text a client can put in a socket frame must decode or be rejected,
+//! never panic.
+
+#![no_main]
+
+use backend::ClientMessage;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(message) = serde_json::from_str::<ClientMessage>(text) {
+        let encoded = serde_json::to_string(&message).expect("decoded messages re-encode");
+        serde_json::from_str::<ClientMessage>(&encoded).expect("re-encoded messages decode");
+    }
+});
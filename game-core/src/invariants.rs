@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::{Game, GamePhase, GiftId, GiftState, PlayerId};
+
+/// A structural rule a `Game` broke. Any of these means a bug in the
+/// reducer, or a hand-edited game.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvariantViolation {
+    #[error("opened gift {0} is not held by a player in the game")]
+    OpenedGiftUnheld(GiftId),
+    #[error("unopened gift {0} has a holder, opener or steals")]
+    UnopenedGiftTouched(GiftId),
+    #[error("player {0} holds more than one gift")]
+    HoldsSeveralGifts(PlayerId),
+    #[error("gift {0} was stolen more often than the rules allow")]
+    StealLimitExceeded(GiftId),
+    #[error("active player does not fit the {0:?} phase")]
+    ActivePlayerMismatch(GamePhase),
+    #[error("turn {0} is past the end of the turn order")]
+    TurnOutOfRange(usize),
+    #[error("turn order lists {0} more than once or is not a player")]
+    TurnOrderMismatch(PlayerId),
+}
+
+impl Game {
+    /// Check the rules that hold between any two actions.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let players: HashSet<&PlayerId> = self.players.iter().map(|p| &p.id).collect();
+        let mut holders = HashSet::new();
+
+        for gift in &self.gifts {
+            match gift.state {
+                GiftState::Opened => {
+                    let holder = gift
+                        .held_by
+                        .as_ref()
+                        .filter(|h| players.contains(h))
+                        .ok_or_else(|| InvariantViolation::OpenedGiftUnheld(gift.id.clone()))?;
+                    if !holders.insert(holder) {
+                        return Err(InvariantViolation::HoldsSeveralGifts(holder.clone()));
+                    }
+                }
+                GiftState::Unopened => {
+                    if gift.held_by.is_some() || gift.opened_by.is_some() || gift.stolen_count > 0 {
+                        return Err(InvariantViolation::UnopenedGiftTouched(gift.id.clone()));
+                    }
+                }
+            }
+            if gift.stolen_count > self.rules.max_steals_per_gift {
+                return Err(InvariantViolation::StealLimitExceeded(gift.id.clone()));
+            }
+        }
+
+        let mut seated = HashSet::new();
+        for player_id in &self.turn_order {
+            if !players.contains(player_id) || !seated.insert(player_id) {
+                return Err(InvariantViolation::TurnOrderMismatch(player_id.clone()));
+            }
+        }
+        if !self.turn_order.is_empty() && self.current_turn >= self.turn_order.len() {
+            return Err(InvariantViolation::TurnOutOfRange(self.current_turn));
+        }
+
+        // Only a game in progress has someone to move, and that someone is
+        // always waiting for a gift: either on their scheduled turn or just
+        // robbed. A game with turns to play always has someone to move.
+        let active_ok = match (&self.phase, &self.active_player) {
+            (GamePhase::InProgress, Some(active)) => {
+                self.turn_order.contains(active) && !holders.contains(active)
+            }
+            (GamePhase::InProgress, None) => self.turn_order.is_empty(),
+            (_, active) => active.is_none(),
+        };
+        if !active_ok {
+            return Err(InvariantViolation::ActivePlayerMismatch(self.phase.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{apply_action, legal_actions, Game, PlayerAction};
+    use proptest::prelude::*;
+    use rand::rngs::mock::StepRng;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn reports_each_kind_of_violation() {
+        let mut game = Game::table(3, &mut StepRng::new(0, 1));
+        assert_eq!(game.check_invariants(), Ok(()));
+        let first = game.turn_order[0].clone();
+        let second = game.turn_order[1].clone();
+
+        let mut broken = game.clone();
+        broken.gifts[0].held_by = Some(first.clone());
+        assert!(matches!(
+            broken.check_invariants(),
+            Err(InvariantViolation::UnopenedGiftTouched(_))
+        ));
+
+        let open = |player_id: &String, gift: usize, game: &mut Game| {
+            let gift_id = game.gifts[gift].id.clone();
+            apply_action(
+                game,
+                PlayerAction::ChooseGift {
+                    player_id: player_id.clone(),
+                    gift_id,
+                },
+            )
+            .unwrap();
+        };
+        open(&first, 0, &mut game);
+        open(&second, 1, &mut game);
+        assert_eq!(game.check_invariants(), Ok(()));
+
+        let mut broken = game.clone();
+        broken.gifts[1].held_by = Some(first.clone());
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::HoldsSeveralGifts(first.clone()))
+        );
+
+        let mut broken = game.clone();
+        broken.gifts[0].held_by = Some("stranger".into());
+        assert!(matches!(
+            broken.check_invariants(),
+            Err(InvariantViolation::OpenedGiftUnheld(_))
+        ));
+
+        let mut broken = game.clone();
+        broken.gifts[0].stolen_count = broken.rules.max_steals_per_gift + 1;
+        assert!(matches!(
+            broken.check_invariants(),
+            Err(InvariantViolation::StealLimitExceeded(_))
+        ));
+
+        let mut broken = game.clone();
+        broken.active_player = Some(first.clone());
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::ActivePlayerMismatch(
+                GamePhase::InProgress
+            ))
+        );
+
+        let mut broken = game.clone();
+        broken.active_player = None;
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::ActivePlayerMismatch(
+                GamePhase::InProgress
+            ))
+        );
+
+        let mut broken = game.clone();
+        broken.phase = GamePhase::Finished;
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::ActivePlayerMismatch(
+                GamePhase::Finished
+            ))
+        );
+
+        let mut broken = game.clone();
+        broken.turn_order[2] = first.clone();
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::TurnOrderMismatch(first))
+        );
+
+        let mut broken = game;
+        broken.current_turn = 3;
+        assert_eq!(
+            broken.check_invariants(),
+            Err(InvariantViolation::TurnOutOfRange(3))
+        );
+    }
+
+    /// One step of a random session: either a legal move picked by index,
+    /// or an arbitrary (usually illegal) action.
+    #[derive(Debug, Clone)]
+    enum Step {
+        Legal(usize),
+        Any {
+            steal: bool,
+            player: usize,
+            gift: usize,
+        },
+    }
+
+    fn step() -> impl Strategy<Value = Step> {
+        prop_oneof![
+            3 => any::<usize>().prop_map(Step::Legal),
+            1 => (any::<bool>(), 0..8usize, 0..9usize)
+                .prop_map(|(steal, player, gift)| Step::Any { steal, player, gift }),
+        ]
+    }
+
+    fn action_for(game: &Game, step: &Step) -> Option<PlayerAction> {
+        match *step {
+            Step::Legal(pick) => {
+                let active = game.active_player.as_ref()?;
+                let legal = legal_actions(game, active);
+                (!legal.is_empty()).then(|| legal[pick % legal.len()].clone())
+            }
+            Step::Any {
+                steal,
+                player,
+                gift,
+            } => {
+                let player_id = game
+                    .players
+                    .get(player)
+                    .map_or_else(|| "nobody".to_string(), |p| p.id.clone());
+                // Indices past the end name a gift that does not exist.
+                let gift_id = game
+                    .gifts
+                    .get(gift)
+                    .map_or_else(|| "missing".to_string(), |g| g.id.clone());
+                Some(if steal {
+                    PlayerAction::StealGift { player_id, gift_id }
+                } else {
+                    PlayerAction::ChooseGift { player_id, gift_id }
+                })
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn random_sessions_keep_invariants(
+            players in 1..7usize,
+            max_steals in 0..5u8,
+            seed in any::<u64>(),
+            steps in prop::collection::vec(step(), 0..80),
+        ) {
+            let mut game = Game::table(players, &mut ChaCha8Rng::seed_from_u64(seed));
+            game.rules.max_steals_per_gift = max_steals;
+            prop_assert_eq!(game.check_invariants(), Ok(()));
+
+            for step in &steps {
+                let Some(action) = action_for(&game, step) else { continue };
+                let before = game.clone();
+                let legal = matches!(step, Step::Legal(_));
+                match apply_action(&mut game, action) {
+                    Ok(_) => prop_assert_eq!(game.check_invariants(), Ok(())),
+                    Err(err) => {
+                        prop_assert!(!legal, "legal action rejected: {}", err);
+                        prop_assert_eq!(&game, &before);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,24 @@
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
+mod invariants;
+pub mod replay;
+pub mod stats;
+pub mod strategy;
+
+pub use invariants::InvariantViolation;
+pub use replay::Timeline;
+pub use stats::GameStats;
+pub use strategy::{Strategy, StrategyKind};
+
+/// Socket protocol version spoken by the server and its clients. Bumped
+/// whenever a change to the server's socket messages would break an existing
+/// client; the server's handshake refuses any other version.
+pub const PROTOCOL_VERSION: u32 = 1;
+
 pub type PlayerId = String;
 pub type GiftId = String;
 
@@ -14,6 +29,8 @@ pub struct Player {
     pub joined_at: u64,
 }
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum GiftState {
@@ -35,6 +52,8 @@ pub struct Gift {
     pub state: GiftState,
 }
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum GamePhase {
@@ -44,6 +63,8 @@ pub enum GamePhase {
     Finished,
 }
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum PlayerAction {
@@ -51,6 +72,26 @@ pub enum PlayerAction {
     StealGift { player_id: PlayerId, gift_id: GiftId },
 }
 
+impl PlayerAction {
+    /// Stable snake_case name matching the serde tag.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PlayerAction::ChooseGift { .. } => "choose_gift",
+            PlayerAction::StealGift { .. } => "steal_gift",
+        }
+    }
+
+    /// The player making the move.
+    pub fn player_id(&self) -> &PlayerId {
+        match self {
+            PlayerAction::ChooseGift { player_id, .. }
+            | PlayerAction::StealGift { player_id, .. } => player_id,
+        }
+    }
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum GameEvent {
@@ -60,6 +101,24 @@ pub enum GameEvent {
     GameFinished,
 }
 
+/// House rules that can vary from party to party.
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct Rules {
+    /// A gift is frozen with its holder once it has been stolen this many times.
+    pub max_steals_per_gift: u8,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            max_steals_per_gift: 3,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Game {
     pub id: String,
@@ -70,14 +129,27 @@ pub struct Game {
     pub current_turn: usize,
     pub active_player: Option<PlayerId>,
     pub history: Vec<GameEvent>,
+    #[serde(default)]
+    pub rules: Rules,
 }
 
 impl Game {
+    #[cfg(feature = "thread-rng")]
     pub fn new(id: impl Into<String>, players: Vec<Player>, gifts: Vec<Gift>) -> Self {
+        Self::with_rng(id, players, gifts, &mut rand::thread_rng())
+    }
+
+    /// Like `new`, with the turn order shuffled by `rng`. Works without
+    /// `thread_rng` (e.g. in the browser) and gives reproducible games.
+    pub fn with_rng<R: Rng + ?Sized>(
+        id: impl Into<String>,
+        players: Vec<Player>,
+        gifts: Vec<Gift>,
+        rng: &mut R,
+    ) -> Self {
         let turn_order = players.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
-        let mut rng = thread_rng();
         let mut shuffled = turn_order.clone();
-        shuffled.shuffle(&mut rng);
+        shuffled.shuffle(rng);
 
         let active = shuffled.first().cloned();
 
@@ -90,8 +162,40 @@ impl Game {
             current_turn: 0,
             active_player: active,
             history: Vec::new(),
+            rules: Rules::default(),
         }
     }
+
+    /// A game for `players` seats named `p1`, `p2`, ..., each bringing one
+    /// wrapped gift, built with `with_rng`. For simulations and tests that
+    /// skip the lobby.
+    pub fn table<R: Rng + ?Sized>(players: usize, rng: &mut R) -> Self {
+        let ids: Vec<String> = (1..=players).map(|i| format!("p{i}")).collect();
+        let players = ids
+            .iter()
+            .map(|id| Player {
+                id: id.clone(),
+                name: id.clone(),
+                joined_at: 0,
+            })
+            .collect();
+        let gifts = ids
+            .iter()
+            .map(|id| Gift {
+                id: format!("gift-{id}"),
+                submitted_by: id.clone(),
+                product_url: String::new(),
+                hint: String::new(),
+                image_url: None,
+                title: None,
+                opened_by: None,
+                held_by: None,
+                stolen_count: 0,
+                state: GiftState::Unopened,
+            })
+            .collect();
+        Self::with_rng("g", players, gifts, rng)
+    }
 }
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -118,34 +222,48 @@ pub enum GameError {
     InvalidAction,
 }
 
-pub fn apply_action(game: &mut Game, action: PlayerAction) -> Result<Vec<GameEvent>, GameError> {
-    if !matches!(game.phase, GamePhase::InProgress) {
-        return Err(GameError::WrongPhase);
+impl GameError {
+    /// Stable snake_case identifier, suitable for metric labels and clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            GameError::WrongPhase => "wrong_phase",
+            GameError::NotPlayersTurn => "not_players_turn",
+            GameError::GiftNotFound => "gift_not_found",
+            GameError::PlayerNotFound => "player_not_found",
+            GameError::GiftAlreadyOpened => "gift_already_opened",
+            GameError::GiftUnopened => "gift_unopened",
+            GameError::CannotStealOwnGift => "cannot_steal_own_gift",
+            GameError::StealLimitReached => "steal_limit_reached",
+            GameError::StealBackNotAllowed => "steal_back_not_allowed",
+            GameError::InvalidAction => "invalid_action",
+        }
     }
+}
 
-    let actor = match &action {
-        PlayerAction::ChooseGift { player_id, .. } => player_id,
-        PlayerAction::StealGift { player_id, .. } => player_id,
-    };
-
-    let active_player = game.active_player.as_ref().ok_or(GameError::InvalidAction)?;
-    if active_player != actor {
-        return Err(GameError::NotPlayersTurn);
-    }
+/// Apply `action` to `game`. Either the whole action is applied or, on
+/// error, `game` is left exactly as it was: every rule is checked before
+/// anything is written.
+pub fn apply_action(game: &mut Game, action: PlayerAction) -> Result<Vec<GameEvent>, GameError> {
+    let planned = plan(game, &action)?;
 
+    // Nothing below can fail.
     let mut events = Vec::new();
-    match action.clone() {
-        PlayerAction::ChooseGift { player_id, gift_id } => {
-            choose_gift(game, &player_id, &gift_id, &mut events)?
+    match (action, planned) {
+        (PlayerAction::ChooseGift { player_id, gift_id }, Move::Open { gift }) => {
+            open_gift(game, gift, player_id, gift_id, &mut events)
         }
-        PlayerAction::StealGift { player_id, gift_id } => {
-            steal_gift(game, &player_id, &gift_id, &mut events)?
+        (PlayerAction::StealGift { player_id, gift_id }, Move::Steal { gift, from }) => {
+            steal_gift(game, gift, from, player_id, gift_id, &mut events)
         }
+        _ => unreachable!("plan matches the action kind"),
     }
 
     // Check for game completion: all gifts opened and each player holds one.
     if all_gifts_opened(&game.gifts) && all_players_holding_one(&game.players, &game.gifts) {
         game.phase = GamePhase::Finished;
+        // Usually the last turn already cleared this, but a robbed player
+        // can open the last gift out of turn in a hand-edited game.
+        game.active_player = None;
         events.push(GameEvent::GameFinished);
     }
 
@@ -153,96 +271,131 @@ pub fn apply_action(game: &mut Game, action: PlayerAction) -> Result<Vec<GameEve
     Ok(events)
 }
 
-fn choose_gift(
-    game: &mut Game,
-    player_id: &PlayerId,
-    gift_id: &GiftId,
-    events: &mut Vec<GameEvent>,
-) -> Result<(), GameError> {
-    let gift = game
-        .gifts
-        .iter_mut()
-        .find(|g| &g.id == gift_id)
-        .ok_or(GameError::GiftNotFound)?;
-
-    if !matches!(gift.state, GiftState::Unopened) {
-        return Err(GameError::GiftAlreadyOpened);
-    }
+/// Check `action` against the rules without touching `game`.
+pub fn validate_action(game: &Game, action: &PlayerAction) -> Result<(), GameError> {
+    plan(game, action).map(|_| ())
+}
 
-    gift.state = GiftState::Opened;
-    gift.opened_by = Some(player_id.clone());
-    gift.held_by = Some(player_id.clone());
-    events.push(GameEvent::GiftOpened {
+/// Every action `player_id` could take right now, opens first.
+pub fn legal_actions(game: &Game, player_id: &PlayerId) -> Vec<PlayerAction> {
+    let opens = game.gifts.iter().map(|g| PlayerAction::ChooseGift {
         player_id: player_id.clone(),
-        gift_id: gift_id.clone(),
+        gift_id: g.id.clone(),
     });
+    let steals = game.gifts.iter().map(|g| PlayerAction::StealGift {
+        player_id: player_id.clone(),
+        gift_id: g.id.clone(),
+    });
+    opens
+        .chain(steals)
+        .filter(|action| validate_action(game, action).is_ok())
+        .collect()
+}
 
-    advance_turn(game, events);
-    Ok(())
+/// What a validated action will do, with the lookups already resolved.
+enum Move {
+    Open { gift: usize },
+    Steal { gift: usize, from: PlayerId },
 }
 
-fn steal_gift(
-    game: &mut Game,
-    player_id: &PlayerId,
-    gift_id: &GiftId,
-    events: &mut Vec<GameEvent>,
-) -> Result<(), GameError> {
+fn plan(game: &Game, action: &PlayerAction) -> Result<Move, GameError> {
+    if !matches!(game.phase, GamePhase::InProgress) {
+        return Err(GameError::WrongPhase);
+    }
+
+    let (actor, gift_id) = match action {
+        PlayerAction::ChooseGift { player_id, gift_id } => (player_id, gift_id),
+        PlayerAction::StealGift { player_id, gift_id } => (player_id, gift_id),
+    };
+
+    let active_player = game.active_player.as_ref().ok_or(GameError::InvalidAction)?;
+    if active_player != actor {
+        return Err(GameError::NotPlayersTurn);
+    }
+
     let gift_index = game
         .gifts
         .iter()
         .position(|g| &g.id == gift_id)
         .ok_or(GameError::GiftNotFound)?;
-    let current_state = game.gifts[gift_index].state.clone();
-    let current_holder = game.gifts[gift_index]
-        .held_by
-        .clone()
-        .ok_or(GameError::InvalidAction)?;
-    let stolen_count = game.gifts[gift_index].stolen_count;
-
-    if !matches!(current_state, GiftState::Opened) {
-        return Err(GameError::GiftUnopened);
-    }
-
-    if current_holder == *player_id {
-        return Err(GameError::CannotStealOwnGift);
-    }
-
-    if stolen_count >= 3 {
-        return Err(GameError::StealLimitReached);
+    let gift = &game.gifts[gift_index];
+
+    match action {
+        PlayerAction::ChooseGift { .. } => {
+            if !matches!(gift.state, GiftState::Unopened) {
+                return Err(GameError::GiftAlreadyOpened);
+            }
+            Ok(Move::Open { gift: gift_index })
+        }
+        PlayerAction::StealGift { .. } => {
+            let current_holder = gift.held_by.clone().ok_or(GameError::InvalidAction)?;
+            if !matches!(gift.state, GiftState::Opened) {
+                return Err(GameError::GiftUnopened);
+            }
+
+            if current_holder == *actor {
+                return Err(GameError::CannotStealOwnGift);
+            }
+
+            if gift.stolen_count >= game.rules.max_steals_per_gift {
+                return Err(GameError::StealLimitReached);
+            }
+
+            if immediate_steal_back(game, actor, &current_holder) {
+                return Err(GameError::StealBackNotAllowed);
+            }
+
+            Ok(Move::Steal {
+                gift: gift_index,
+                from: current_holder,
+            })
+        }
     }
+}
 
-    if immediate_steal_back(game, player_id, &current_holder) {
-        return Err(GameError::StealBackNotAllowed);
-    }
+fn open_gift(
+    game: &mut Game,
+    gift_index: usize,
+    player_id: PlayerId,
+    gift_id: GiftId,
+    events: &mut Vec<GameEvent>,
+) {
+    let gift = &mut game.gifts[gift_index];
+    gift.state = GiftState::Opened;
+    gift.opened_by = Some(player_id.clone());
+    gift.held_by = Some(player_id.clone());
+    events.push(GameEvent::GiftOpened { player_id, gift_id });
 
-    let gift = game
-        .gifts
-        .get_mut(gift_index)
-        .ok_or(GameError::GiftNotFound)?;
+    advance_turn(game, events);
+}
 
+fn steal_gift(
+    game: &mut Game,
+    gift_index: usize,
+    from: PlayerId,
+    player_id: PlayerId,
+    gift_id: GiftId,
+    events: &mut Vec<GameEvent>,
+) {
+    let gift = &mut game.gifts[gift_index];
     gift.stolen_count += 1;
     gift.held_by = Some(player_id.clone());
 
     events.push(GameEvent::GiftStolen {
-        from: current_holder.clone(),
-        to: player_id.clone(),
-        gift_id: gift_id.clone(),
+        from: from.clone(),
+        to: player_id,
+        gift_id,
     });
 
     // Forced steal chain: victim acts next; current_turn does not advance.
-    game.active_player = Some(current_holder.clone());
-    events.push(GameEvent::TurnChanged {
-        player_id: current_holder,
-    });
-
-    Ok(())
+    game.active_player = Some(from.clone());
+    events.push(GameEvent::TurnChanged { player_id: from });
 }
 
 fn advance_turn(game: &mut Game, events: &mut Vec<GameEvent>) {
-    let next_index = game.current_turn + 1;
-    if next_index < game.turn_order.len() {
+    let next_index = game.current_turn.saturating_add(1);
+    if let Some(next_player) = game.turn_order.get(next_index).cloned() {
         game.current_turn = next_index;
-        let next_player = game.turn_order[next_index].clone();
         game.active_player = Some(next_player.clone());
         events.push(GameEvent::TurnChanged {
             player_id: next_player,
@@ -253,9 +406,13 @@ fn advance_turn(game: &mut Game, events: &mut Vec<GameEvent>) {
     }
 }
 
+/// Whether the last move was `target` stealing from `actor`. Turn changes
+/// are bookkeeping, not moves, so they are skipped.
 fn immediate_steal_back(game: &Game, actor: &PlayerId, target: &PlayerId) -> bool {
     game.history
-        .last()
+        .iter()
+        .rev()
+        .find(|evt| !matches!(evt, GameEvent::TurnChanged { .. }))
         .map(|evt| match evt {
             GameEvent::GiftStolen { from, to, .. } => from == actor && to == target,
             _ => false,
@@ -268,7 +425,7 @@ fn all_gifts_opened(gifts: &[Gift]) -> bool {
 }
 
 fn all_players_holding_one(players: &[Player], gifts: &[Gift]) -> bool {
-    let mut holder_counts: HashMap<&PlayerId, u8> = HashMap::new();
+    let mut holder_counts: HashMap<&PlayerId, usize> = HashMap::new();
     for gift in gifts {
         if let Some(holder) = &gift.held_by {
             let count = holder_counts.entry(holder).or_insert(0);
@@ -336,9 +493,65 @@ mod tests {
             current_turn: 0,
             active_player: Some("p1".into()),
             history: vec![],
+            rules: Rules::default(),
         }
     }
 
+    #[test]
+    fn legal_actions_match_what_apply_accepts() {
+        let mut game = base_game();
+        apply_action(
+            &mut game,
+            PlayerAction::ChooseGift {
+                player_id: "p1".into(),
+                gift_id: "g1".into(),
+            },
+        )
+        .unwrap();
+        let before = game.clone();
+
+        let legal = legal_actions(&game, &"p2".to_string());
+        assert_eq!(
+            legal,
+            vec![
+                PlayerAction::ChooseGift {
+                    player_id: "p2".into(),
+                    gift_id: "g2".into(),
+                },
+                PlayerAction::ChooseGift {
+                    player_id: "p2".into(),
+                    gift_id: "g3".into(),
+                },
+                PlayerAction::StealGift {
+                    player_id: "p2".into(),
+                    gift_id: "g1".into(),
+                },
+            ]
+        );
+        assert!(legal_actions(&game, &"p1".to_string()).is_empty());
+        assert_eq!(
+            validate_action(
+                &game,
+                &PlayerAction::ChooseGift {
+                    player_id: "p3".into(),
+                    gift_id: "g2".into(),
+                }
+            ),
+            Err(GameError::NotPlayersTurn)
+        );
+        assert_eq!(game, before);
+    }
+
+    #[test]
+    fn same_rng_gives_same_turn_order() {
+        use rand::rngs::mock::StepRng;
+        let players = || vec![player("p1"), player("p2"), player("p3"), player("p4")];
+        let a = Game::with_rng("a", players(), vec![], &mut StepRng::new(7, 13));
+        let b = Game::with_rng("b", players(), vec![], &mut StepRng::new(7, 13));
+        assert_eq!(a.turn_order, b.turn_order);
+        assert_eq!(a.active_player.as_ref(), a.turn_order.first());
+    }
+
     #[test]
     fn open_gift_happy_path_advances_turn() {
         let mut game = base_game();
@@ -431,6 +644,29 @@ mod tests {
         assert_eq!(err, GameError::StealLimitReached);
     }
 
+    #[test]
+    fn custom_steal_limit_is_enforced() {
+        let mut game = base_game();
+        game.rules.max_steals_per_gift = 1;
+        game.gifts[0] = Gift {
+            stolen_count: 1,
+            ..opened_gift("g1", "p1")
+        };
+        game.active_player = Some("p2".into());
+        game.current_turn = 1;
+
+        let err = apply_action(
+            &mut game,
+            PlayerAction::StealGift {
+                player_id: "p2".into(),
+                gift_id: "g1".into(),
+            },
+        )
+        .unwrap_err();
+
+        assert_eq!(err, GameError::StealLimitReached);
+    }
+
     #[test]
     fn reject_immediate_steal_back() {
         let mut game = base_game();
@@ -461,6 +697,108 @@ mod tests {
         assert_eq!(err, GameError::StealBackNotAllowed);
     }
 
+    #[test]
+    fn steal_back_is_rejected_in_play() {
+        let mut game = base_game();
+        let mut play = |player: &str, gift: &str, steal: bool| {
+            let (player_id, gift_id) = (player.to_string(), gift.to_string());
+            apply_action(
+                &mut game,
+                if steal {
+                    PlayerAction::StealGift { player_id, gift_id }
+                } else {
+                    PlayerAction::ChooseGift { player_id, gift_id }
+                },
+            )
+        };
+        play("p1", "g1", false).unwrap();
+        play("p2", "g1", true).unwrap();
+        assert_eq!(play("p1", "g1", true), Err(GameError::StealBackNotAllowed));
+        // Opening something else is still fine, and ends the chain.
+        play("p1", "g2", false).unwrap();
+        play("p3", "g1", true).unwrap();
+        play("p2", "g2", true).unwrap();
+    }
+
+    /// A game and an action that `apply_action` rejects with `err`, or
+    /// `None` for errors it never raises.
+    fn rejection(err: &GameError) -> Option<(Game, PlayerAction)> {
+        let choose = |player: &str, gift: &str| PlayerAction::ChooseGift {
+            player_id: player.into(),
+            gift_id: gift.into(),
+        };
+        let steal = |player: &str, gift: &str| PlayerAction::StealGift {
+            player_id: player.into(),
+            gift_id: gift.into(),
+        };
+        // p1 has opened g1 and it is p2's turn.
+        let mut opened = base_game();
+        apply_action(&mut opened, choose("p1", "g1")).unwrap();
+
+        Some(match err {
+            GameError::WrongPhase => {
+                let mut game = base_game();
+                game.phase = GamePhase::Submissions;
+                (game, choose("p1", "g1"))
+            }
+            GameError::NotPlayersTurn => (base_game(), choose("p2", "g1")),
+            GameError::GiftNotFound => (opened, steal("p2", "nope")),
+            // Resolving players is left to callers.
+            GameError::PlayerNotFound => return None,
+            GameError::GiftAlreadyOpened => (opened, choose("p2", "g1")),
+            GameError::GiftUnopened => {
+                opened.gifts[1].held_by = Some("p3".into());
+                (opened, steal("p2", "g2"))
+            }
+            GameError::CannotStealOwnGift => {
+                opened.gifts[0].held_by = Some("p2".into());
+                (opened, steal("p2", "g1"))
+            }
+            GameError::StealLimitReached => {
+                opened.gifts[0].stolen_count = opened.rules.max_steals_per_gift;
+                (opened, steal("p2", "g1"))
+            }
+            GameError::StealBackNotAllowed => {
+                apply_action(&mut opened, steal("p2", "g1")).unwrap();
+                // p1 was just robbed by p2 and may not take it straight back.
+                (opened, steal("p1", "g1"))
+            }
+            GameError::InvalidAction => {
+                let mut game = base_game();
+                game.active_player = None;
+                (game, choose("p1", "g1"))
+            }
+        })
+    }
+
+    #[test]
+    fn rejected_actions_leave_the_game_untouched() {
+        let all = [
+            GameError::WrongPhase,
+            GameError::NotPlayersTurn,
+            GameError::GiftNotFound,
+            GameError::PlayerNotFound,
+            GameError::GiftAlreadyOpened,
+            GameError::GiftUnopened,
+            GameError::CannotStealOwnGift,
+            GameError::StealLimitReached,
+            GameError::StealBackNotAllowed,
+            GameError::InvalidAction,
+        ];
+        for expected in all {
+            let Some((before, action)) = rejection(&expected) else {
+                continue;
+            };
+            let mut after = before.clone();
+            assert_eq!(
+                validate_action(&before, &action).map_err(|e| e.code()),
+                Err(expected.code())
+            );
+            assert_eq!(apply_action(&mut after, action), Err(expected));
+            assert_eq!(after, before);
+        }
+    }
+
     #[test]
     fn forced_chain_advances_when_new_gift_opened() {
         let mut game = base_game();
@@ -502,6 +840,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn finishing_out_of_turn_clears_the_active_player() {
+        // Hand-edited: p3 already holds a gift before their turn, so the
+        // robbed p1 opens the last gift mid-order.
+        let mut game = base_game();
+        game.gifts[0] = opened_gift("g1", "p2");
+        game.gifts[1] = opened_gift("g2", "p3");
+        game.current_turn = 1;
+
+        let events = apply_action(
+            &mut game,
+            PlayerAction::ChooseGift {
+                player_id: "p1".into(),
+                gift_id: "g3".into(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(game.phase, GamePhase::Finished);
+        assert_eq!(game.active_player, None);
+        assert_eq!(events.last(), Some(&GameEvent::GameFinished));
+        assert_eq!(game.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn last_possible_turn_ends_without_overflow() {
+        let mut game = base_game();
+        game.current_turn = usize::MAX;
+
+        apply_action(
+            &mut game,
+            PlayerAction::ChooseGift {
+                player_id: "p1".into(),
+                gift_id: "g1".into(),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(game.current_turn, usize::MAX);
+        assert_eq!(game.active_player, None);
+    }
+
     #[test]
     fn rejects_wrong_turn_or_phase() {
         let mut game = base_game();
@@ -528,4 +908,36 @@ mod tests {
         .unwrap_err();
         assert_eq!(err, GameError::NotPlayersTurn);
     }
+
+    /// A fuzz seed, plus the result code each action is expected to give.
+    /// The fuzz target ignores `expect`.
+    #[derive(Deserialize)]
+    struct Seed {
+        game: Game,
+        actions: Vec<PlayerAction>,
+        expect: Vec<String>,
+    }
+
+    #[test]
+    fn fuzz_seeds_parse_and_replay_as_recorded() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../fuzz/seeds/action_sequence");
+        let mut seen = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let text = std::fs::read_to_string(&path).unwrap();
+            let Seed {
+                mut game,
+                actions,
+                expect,
+            } = serde_json::from_str(&text)
+                .unwrap_or_else(|e| panic!("{} no longer parses: {e}", path.display()));
+            assert_eq!(actions.len(), expect.len(), "{}", path.display());
+            for (action, expected) in actions.into_iter().zip(expect) {
+                let got = apply_action(&mut game, action).map_or_else(|e| e.code(), |_| "ok");
+                assert_eq!(got, expected, "{}", path.display());
+            }
+            seen += 1;
+        }
+        assert!(seen > 0, "no seeds in {dir}");
+    }
 }
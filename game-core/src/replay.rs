@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{GameEvent, GiftId, PlayerId};
+
+/// Step-by-step reconstruction of a game, for scrubbing through a replay.
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Timeline {
+    pub turn_order: Vec<PlayerId>,
+    /// Who was up before the first event.
+    pub initial_active_player: Option<PlayerId>,
+    pub steps: Vec<TimelineStep>,
+}
+
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimelineStep {
+    pub index: usize,
+    pub event: GameEvent,
+    /// Who is up once this event has been applied.
+    pub active_player: Option<PlayerId>,
+    /// Opened gifts in the order they were opened, with their holder after
+    /// this event.
+    pub holdings: Vec<Holding>,
+}
+
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Holding {
+    pub gift_id: GiftId,
+    pub held_by: PlayerId,
+    pub stolen_count: u8,
+}
+
+impl Timeline {
+    pub fn from_history(turn_order: &[PlayerId], history: &[GameEvent]) -> Self {
+        let initial_active_player = turn_order.first().cloned();
+        let mut active = initial_active_player.clone();
+        let mut holdings: Vec<Holding> = Vec::new();
+        let mut steps = Vec::with_capacity(history.len());
+
+        for (index, event) in history.iter().enumerate() {
+            match event {
+                GameEvent::GiftOpened { player_id, gift_id } => holdings.push(Holding {
+                    gift_id: gift_id.clone(),
+                    held_by: player_id.clone(),
+                    stolen_count: 0,
+                }),
+                GameEvent::GiftStolen { to, gift_id, .. } => {
+                    if let Some(holding) = holdings.iter_mut().find(|h| &h.gift_id == gift_id) {
+                        holding.held_by = to.clone();
+                        holding.stolen_count = holding.stolen_count.saturating_add(1);
+                    }
+                }
+                GameEvent::TurnChanged { player_id } => active = Some(player_id.clone()),
+                GameEvent::GameFinished => active = None,
+            }
+            steps.push(TimelineStep {
+                index,
+                event: event.clone(),
+                active_player: active.clone(),
+                holdings: holdings.clone(),
+            });
+        }
+
+        Self {
+            turn_order: turn_order.to_vec(),
+            initial_active_player,
+            steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{apply_action, Game, GamePhase, Gift, GiftState, Player, PlayerAction, Rules};
+
+    #[test]
+    fn replay_matches_live_game() {
+        let players: Vec<Player> = ["p1", "p2"]
+            .iter()
+            .map(|id| Player {
+                id: id.to_string(),
+                name: id.to_string(),
+                joined_at: 0,
+            })
+            .collect();
+        let gifts: Vec<Gift> = ["g1", "g2"]
+            .iter()
+            .map(|id| Gift {
+                id: id.to_string(),
+                submitted_by: "p1".into(),
+                product_url: String::new(),
+                hint: String::new(),
+                image_url: None,
+                title: None,
+                opened_by: None,
+                held_by: None,
+                stolen_count: 0,
+                state: GiftState::Unopened,
+            })
+            .collect();
+        let mut game = Game {
+            id: "game".into(),
+            phase: GamePhase::InProgress,
+            players,
+            gifts,
+            turn_order: vec!["p1".into(), "p2".into()],
+            current_turn: 0,
+            active_player: Some("p1".into()),
+            history: vec![],
+            rules: Rules::default(),
+        };
+        for action in [
+            PlayerAction::ChooseGift {
+                player_id: "p1".into(),
+                gift_id: "g1".into(),
+            },
+            PlayerAction::StealGift {
+                player_id: "p2".into(),
+                gift_id: "g1".into(),
+            },
+            PlayerAction::ChooseGift {
+                player_id: "p1".into(),
+                gift_id: "g2".into(),
+            },
+        ] {
+            apply_action(&mut game, action).unwrap();
+        }
+
+        let timeline = Timeline::from_history(&game.turn_order, &game.history);
+        assert_eq!(timeline.initial_active_player.as_deref(), Some("p1"));
+        assert_eq!(timeline.steps.len(), game.history.len());
+
+        // after the steal, p1 is forced to act and p2 holds g1
+        let steal = &timeline.steps[2];
+        assert!(matches!(steal.event, GameEvent::GiftStolen { .. }));
+        assert_eq!(steal.holdings[0].held_by, "p2");
+        assert_eq!(steal.holdings[0].stolen_count, 1);
+        assert_eq!(timeline.steps[3].active_player.as_deref(), Some("p1"));
+
+        let last = timeline.steps.last().unwrap();
+        assert_eq!(last.event, GameEvent::GameFinished);
+        assert_eq!(last.active_player, None);
+        for gift in &game.gifts {
+            let holding = last
+                .holdings
+                .iter()
+                .find(|h| h.gift_id == gift.id)
+                .unwrap();
+            assert_eq!(Some(&holding.held_by), gift.held_by.as_ref());
+            assert_eq!(holding.stolen_count, gift.stolen_count);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameEvent, GiftId, PlayerId};
+
+/// End-of-party superlatives, derived purely from a game's event history.
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GameStats {
+    pub total_steals: usize,
+    /// Gift stolen the most times; ties go to the gift that got there first.
+    pub most_stolen_gift: Option<GiftTally>,
+    /// Most steals in a row before someone opened a fresh gift.
+    pub longest_steal_chain: usize,
+    /// Player who had gifts taken from them the most; ties go the same way.
+    pub most_robbed_player: Option<PlayerTally>,
+    /// Gifts still held by whoever opened them.
+    pub kept_by_opener: Vec<GiftId>,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GiftTally {
+    pub gift_id: GiftId,
+    pub count: usize,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PlayerTally {
+    pub player_id: PlayerId,
+    pub count: usize,
+}
+
+impl GameStats {
+    pub fn from_history(history: &[GameEvent]) -> Self {
+        let mut stats = GameStats::default();
+        let mut steals_by_gift = Tallies::default();
+        let mut losses_by_player = Tallies::default();
+        let mut openers: Vec<(GiftId, PlayerId)> = Vec::new();
+        let mut holders: HashMap<&GiftId, &PlayerId> = HashMap::new();
+        let mut chain = 0;
+
+        for event in history {
+            match event {
+                GameEvent::GiftOpened { player_id, gift_id } => {
+                    chain = 0;
+                    openers.push((gift_id.clone(), player_id.clone()));
+                    holders.insert(gift_id, player_id);
+                }
+                GameEvent::GiftStolen { from, to, gift_id } => {
+                    stats.total_steals += 1;
+                    chain += 1;
+                    stats.longest_steal_chain = stats.longest_steal_chain.max(chain);
+                    steals_by_gift.bump(gift_id);
+                    losses_by_player.bump(from);
+                    holders.insert(gift_id, to);
+                }
+                GameEvent::TurnChanged { .. } | GameEvent::GameFinished => {}
+            }
+        }
+
+        stats.most_stolen_gift = steals_by_gift
+            .leader
+            .map(|(gift_id, count)| GiftTally { gift_id, count });
+        stats.most_robbed_player = losses_by_player
+            .leader
+            .map(|(player_id, count)| PlayerTally { player_id, count });
+        stats.kept_by_opener = openers
+            .into_iter()
+            .filter(|(gift, opener)| holders.get(gift) == Some(&opener))
+            .map(|(gift, _)| gift)
+            .collect();
+        stats
+    }
+}
+
+/// Running counts plus the current leader. The leader only changes when a
+/// key strictly passes it, so ties go to whichever key reached the count first.
+#[derive(Default)]
+struct Tallies {
+    counts: HashMap<String, usize>,
+    leader: Option<(String, usize)>,
+}
+
+impl Tallies {
+    fn bump(&mut self, key: &str) {
+        let count = self.counts.entry(key.to_string()).or_default();
+        *count += 1;
+        if self.leader.as_ref().is_none_or(|(_, best)| *count > *best) {
+            self.leader = Some((key.to_string(), *count));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opened(player: &str, gift: &str) -> GameEvent {
+        GameEvent::GiftOpened {
+            player_id: player.into(),
+            gift_id: gift.into(),
+        }
+    }
+
+    fn stolen(from: &str, to: &str, gift: &str) -> GameEvent {
+        GameEvent::GiftStolen {
+            from: from.into(),
+            to: to.into(),
+            gift_id: gift.into(),
+        }
+    }
+
+    #[test]
+    fn computes_superlatives_from_history() {
+        let history = vec![
+            opened("p1", "g1"),
+            opened("p2", "g2"),
+            // p3 takes g1, p1 takes g2, p2 takes g1 from p3: a chain of 3
+            stolen("p1", "p3", "g1"),
+            stolen("p2", "p1", "g2"),
+            stolen("p3", "p2", "g1"),
+            opened("p3", "g3"),
+            stolen("p3", "p1", "g3"),
+            opened("p3", "g4"),
+            GameEvent::GameFinished,
+        ];
+
+        let stats = GameStats::from_history(&history);
+        assert_eq!(stats.total_steals, 4);
+        assert_eq!(stats.longest_steal_chain, 3);
+        assert_eq!(
+            stats.most_stolen_gift,
+            Some(GiftTally {
+                gift_id: "g1".into(),
+                count: 2
+            })
+        );
+        assert_eq!(
+            stats.most_robbed_player,
+            Some(PlayerTally {
+                player_id: "p3".into(),
+                count: 2
+            })
+        );
+        assert_eq!(stats.kept_by_opener, vec!["g4".to_string()]);
+    }
+
+    #[test]
+    fn ties_go_to_the_first_gift_to_reach_the_top_count() {
+        let history = vec![
+            opened("p1", "g1"),
+            opened("p2", "g2"),
+            // g1 is stolen first, but g2 is the first to be stolen twice
+            stolen("p1", "p3", "g1"),
+            stolen("p2", "p1", "g2"),
+            stolen("p1", "p2", "g2"),
+            stolen("p3", "p1", "g1"),
+        ];
+
+        let stats = GameStats::from_history(&history);
+        assert_eq!(
+            stats.most_stolen_gift,
+            Some(GiftTally {
+                gift_id: "g2".into(),
+                count: 2
+            })
+        );
+        assert_eq!(
+            stats.most_robbed_player,
+            Some(PlayerTally {
+                player_id: "p1".into(),
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn empty_history_has_no_leaders() {
+        let stats = GameStats::from_history(&[]);
+        assert_eq!(stats, GameStats::default());
+    }
+}
@@ -0,0 +1,171 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{legal_actions, Game, PlayerAction, PlayerId};
+
+/// How a computer player picks its move. Only ever returns legal actions.
+pub trait Strategy {
+    /// The move `player_id` makes, or `None` if it has none right now
+    /// (not its turn, or the game is over).
+    fn choose(&mut self, game: &Game, player_id: &PlayerId) -> Option<PlayerAction>;
+}
+
+/// Any legal move, uniformly.
+pub struct Random<R> {
+    rng: R,
+}
+
+impl<R: Rng> Random<R> {
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+}
+
+impl<R: Rng> Strategy for Random<R> {
+    fn choose(&mut self, game: &Game, player_id: &PlayerId) -> Option<PlayerAction> {
+        legal_actions(game, player_id)
+            .choose(&mut self.rng)
+            .cloned()
+    }
+}
+
+/// Steal the gift that has been stolen the most, on the theory that the
+/// room wants it; open a fresh one only when nothing can be stolen.
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn choose(&mut self, game: &Game, player_id: &PlayerId) -> Option<PlayerAction> {
+        let legal = legal_actions(game, player_id);
+        let stolen_count = |action: &PlayerAction| match action {
+            PlayerAction::StealGift { gift_id, .. } => game
+                .gifts
+                .iter()
+                .find(|g| &g.id == gift_id)
+                .map(|g| g.stolen_count),
+            PlayerAction::ChooseGift { .. } => None,
+        };
+        // Ties go to the earliest gift.
+        let mut best: Option<(&PlayerAction, u8)> = None;
+        for action in &legal {
+            if let Some(count) = stolen_count(action) {
+                if best.is_none_or(|(_, most)| count > most) {
+                    best = Some((action, count));
+                }
+            }
+        }
+        best.map(|(action, _)| action.clone())
+            .or_else(|| legal.into_iter().next())
+    }
+}
+
+/// Never steal unless there is nothing left to open.
+pub struct AlwaysOpen;
+
+impl Strategy for AlwaysOpen {
+    fn choose(&mut self, game: &Game, player_id: &PlayerId) -> Option<PlayerAction> {
+        // Opens come first in `legal_actions`.
+        legal_actions(game, player_id).into_iter().next()
+    }
+}
+
+/// Names the built-in strategies, for config files and requests.
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    Random,
+    Greedy,
+    AlwaysOpen,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 3] = [
+        StrategyKind::Random,
+        StrategyKind::Greedy,
+        StrategyKind::AlwaysOpen,
+    ];
+
+    /// Stable snake_case name matching the serde tag.
+    pub fn name(self) -> &'static str {
+        match self {
+            StrategyKind::Random => "random",
+            StrategyKind::Greedy => "greedy",
+            StrategyKind::AlwaysOpen => "always_open",
+        }
+    }
+
+    /// Build the strategy; `rng` is only used by `Random`.
+    pub fn build<R: Rng + 'static>(self, rng: R) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Random => Box::new(Random::new(rng)),
+            StrategyKind::Greedy => Box::new(Greedy),
+            StrategyKind::AlwaysOpen => Box::new(AlwaysOpen),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{apply_action, Game, GamePhase};
+    use rand::rngs::mock::StepRng;
+
+    fn game(players: usize) -> Game {
+        Game::table(players, &mut StepRng::new(3, 5))
+    }
+
+    fn play_out(game: &mut Game, strategy: &mut dyn Strategy) -> usize {
+        let mut moves = 0;
+        while let Some(player_id) = game.active_player.clone() {
+            let action = strategy
+                .choose(game, &player_id)
+                .expect("active player can move");
+            apply_action(game, action).unwrap();
+            moves += 1;
+        }
+        moves
+    }
+
+    #[test]
+    fn every_strategy_finishes_a_game() {
+        for kind in StrategyKind::ALL {
+            let mut game = game(5);
+            let moves = play_out(&mut game, kind.build(StepRng::new(1, 7)).as_mut());
+            assert_eq!(game.phase, GamePhase::Finished, "{}", kind.name());
+            assert!(moves >= 5);
+            if kind == StrategyKind::AlwaysOpen {
+                assert_eq!(moves, 5);
+            }
+        }
+        assert_eq!(Greedy.choose(&game(2), &"nobody".to_string()), None);
+    }
+
+    #[test]
+    fn greedy_steals_the_most_stolen_gift() {
+        let mut game = game(4);
+        let first = game.turn_order[0].clone();
+        let mut opener = AlwaysOpen;
+        for _ in 0..3 {
+            let player_id = game.active_player.clone().unwrap();
+            let action = opener.choose(&game, &player_id).unwrap();
+            apply_action(&mut game, action).unwrap();
+        }
+        let hot = game
+            .gifts
+            .iter_mut()
+            .find(|g| g.held_by.as_ref() == Some(&first))
+            .unwrap();
+        hot.stolen_count = 2;
+        let hot = hot.id.clone();
+
+        let last = game.active_player.clone().unwrap();
+        assert_eq!(
+            Greedy.choose(&game, &last),
+            Some(PlayerAction::StealGift {
+                player_id: last,
+                gift_id: hot,
+            })
+        );
+    }
+}
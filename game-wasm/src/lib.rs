@@ -0,0 +1,154 @@
+//! Browser bindings for game-core, so the UI can grey out illegal moves and
+//! preview outcomes with the same rules the server runs. Games, actions and
+//! events cross the boundary as plain JS objects in their serde shape, so
+//! the server's `state` message can be passed in as the game unchanged; rule
+//! violations are thrown as errors whose message is the `GameError` code.
+
+use game_core::{Game, GameError, GameEvent, PlayerAction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[derive(Serialize)]
+struct Applied {
+    game: Game,
+    events: Vec<GameEvent>,
+}
+
+/// Apply `action` to a copy of `game` and return `{ game, events }`.
+#[wasm_bindgen(js_name = applyAction)]
+pub fn apply_action(game: JsValue, action: JsValue) -> Result<JsValue, JsError> {
+    let mut game: Game = from_js(game)?;
+    let action: PlayerAction = from_js(action)?;
+    let events = game_core::apply_action(&mut game, action).map_err(rule_error)?;
+    to_js(&Applied { game, events })
+}
+
+/// Throw if `action` would be rejected; otherwise return nothing.
+#[wasm_bindgen(js_name = validateAction)]
+pub fn validate_action(game: JsValue, action: JsValue) -> Result<(), JsError> {
+    let game: Game = from_js(game)?;
+    let action: PlayerAction = from_js(action)?;
+    game_core::validate_action(&game, &action).map_err(rule_error)
+}
+
+/// Every action `player_id` could take right now.
+#[wasm_bindgen(js_name = legalActions)]
+pub fn legal_actions(game: JsValue, player_id: String) -> Result<JsValue, JsError> {
+    let game: Game = from_js(game)?;
+    to_js(&game_core::legal_actions(&game, &player_id))
+}
+
+fn rule_error(err: GameError) -> JsError {
+    JsError::new(err.code())
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, JsError> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    // Plain objects and `null` rather than `Map`s and `undefined`, matching
+    // what the server sends as JSON.
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+// Run with `wasm-bindgen-test-runner` as the wasm32 runner:
+// `CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner
+//  cargo test -p game-wasm --target wasm32-unknown-unknown`.
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// A `state` message as the server sends it: p1 has opened g1 and it is
+    /// p2's turn. Fields the rules do not know, like `online`, are ignored.
+    const STATE: &str = r#"{
+        "id": "g",
+        "phase": "in_progress",
+        "players": [
+            { "id": "p1", "name": "Ann", "joined_at": 0, "online": true },
+            { "id": "p2", "name": "Bo", "joined_at": 0, "online": false }
+        ],
+        "gifts": [
+            { "id": "g1", "submitted_by": "p2", "product_url": "", "hint": "soft",
+              "image_url": null, "title": null, "opened_by": "p1", "held_by": "p1",
+              "stolen_count": 0, "state": "opened" },
+            { "id": "g2", "submitted_by": "", "product_url": "", "hint": "square",
+              "image_url": null, "title": null, "opened_by": null, "held_by": null,
+              "stolen_count": 0, "state": "unopened" }
+        ],
+        "turn_order": ["p1", "p2"],
+        "current_turn": 1,
+        "active_player": "p2",
+        "history": [
+            { "type": "gift_opened", "player_id": "p1", "gift_id": "g1" },
+            { "type": "turn_changed", "player_id": "p2" }
+        ],
+        "rules": { "max_steals_per_gift": 3 },
+        "spectators": 2
+    }"#;
+
+    fn js(json: &str) -> JsValue {
+        js_sys::JSON::parse(json).unwrap()
+    }
+
+    fn steal(player: &str, gift: &str) -> JsValue {
+        js(&format!(
+            r#"{{ "steal_gift": {{ "player_id": "{player}", "gift_id": "{gift}" }} }}"#
+        ))
+    }
+
+    fn message(err: JsError) -> String {
+        js_sys::Error::from(JsValue::from(err)).message().into()
+    }
+
+    #[wasm_bindgen_test]
+    fn lists_legal_actions_for_the_active_player() {
+        let actions: Vec<PlayerAction> =
+            from_js(legal_actions(js(STATE), "p2".into()).unwrap()).unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                PlayerAction::ChooseGift {
+                    player_id: "p2".into(),
+                    gift_id: "g2".into(),
+                },
+                PlayerAction::StealGift {
+                    player_id: "p2".into(),
+                    gift_id: "g1".into(),
+                },
+            ]
+        );
+        let waiting: Vec<PlayerAction> =
+            from_js(legal_actions(js(STATE), "p1".into()).unwrap()).unwrap();
+        assert!(waiting.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn applies_an_action_to_a_copy() {
+        let state = js(STATE);
+        let applied = apply_action(state.clone(), steal("p2", "g1")).unwrap();
+        let game: Game = from_js(js_sys::Reflect::get(&applied, &"game".into()).unwrap()).unwrap();
+        let events: Vec<GameEvent> =
+            from_js(js_sys::Reflect::get(&applied, &"events".into()).unwrap()).unwrap();
+
+        assert_eq!(game.gifts[0].held_by.as_deref(), Some("p2"));
+        assert_eq!(game.active_player.as_deref(), Some("p1"));
+        assert!(matches!(&events[0], GameEvent::GiftStolen { from, .. } if from == "p1"));
+        // The caller's object is left alone.
+        let before: Game = from_js(state).unwrap();
+        assert_eq!(before.gifts[0].held_by.as_deref(), Some("p1"));
+    }
+
+    #[wasm_bindgen_test]
+    fn rejected_actions_throw_their_code() {
+        let err = validate_action(js(STATE), steal("p1", "g1")).unwrap_err();
+        assert_eq!(message(err), "not_players_turn");
+        let err = apply_action(js(STATE), steal("p2", "g9")).unwrap_err();
+        assert_eq!(message(err), "gift_not_found");
+        assert!(validate_action(js(STATE), steal("p2", "g1")).is_ok());
+    }
+}